@@ -0,0 +1,46 @@
+use std::{env, fs, path::Path};
+
+// Registers every `src/dayNN.rs` with the `days!` macro in main.rs, so adding a day is
+// just adding its file and its `mod` line. The `mod` lines stay in main.rs, where rustfmt
+// can find the days, and this checks they match the files.
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let src = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src");
+    let mut days: Vec<String> = fs::read_dir(&src)
+        .expect("couldn't read src")
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            day_number(name.strip_suffix(".rs")?)
+        })
+        .collect();
+    days.sort();
+
+    let main = fs::read_to_string(src.join("main.rs")).expect("couldn't read src/main.rs");
+    let mut declared: Vec<String> = main
+        .lines()
+        .filter_map(|line| day_number(line.strip_prefix("mod ")?.strip_suffix(';')?))
+        .collect();
+    declared.sort();
+
+    let mut generated = String::new();
+    for day in days.iter().filter(|day| !declared.contains(day)) {
+        generated.push_str(&format!(
+            "compile_error!(\"src/day{day}.rs isn't declared, add `mod day{day};` to src/main.rs\");\n"
+        ));
+    }
+    generated.push_str("days! {\n");
+    for day in &days {
+        generated.push_str(&format!("    Day{} => day{}::Day{},\n", day, day, day));
+    }
+    generated.push_str("}\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("days.rs");
+    fs::write(out, generated).expect("couldn't write the day registry");
+}
+
+// "day07" is day 07
+fn day_number(name: &str) -> Option<String> {
+    let day = name.strip_prefix("day")?;
+    (day.len() == 2 && day.chars().all(|c| c.is_ascii_digit())).then(|| String::from(day))
+}
@@ -0,0 +1,63 @@
+use std::{collections::HashMap, fs};
+
+use crate::solution::Answer;
+
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+pub enum Verdict<'a> {
+    Pass(&'a str),
+    Fail(&'a str, &'a str),
+    Error(&'a str),
+    Unknown(&'a str),
+}
+
+// one answer per line: `<input> <part> <answer>`, e.g. `day01.example 1 24000`.
+// multi-line answers are written with `\n` escapes, and `#` starts a comment.
+pub fn load(filename: &str) -> Result<HashMap<(String, u8), String>, String> {
+    let mut known = HashMap::new();
+    let raw = match fs::read_to_string(filename) {
+        Ok(raw) => raw,
+        Err(_) => return Ok(known),
+    };
+
+    for (i, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parts: Vec<&str> = line.splitn(3, ' ').collect();
+        if parts.len() != 3 {
+            return Err(format!(
+                "{}:{}: expected `<input> <part> <answer>`, got {:?}",
+                filename,
+                i + 1,
+                line
+            ));
+        }
+        let part: u8 = parts[1].parse().map_err(|_err| {
+            format!("{}:{}: {:?} isn't a part number", filename, i + 1, parts[1])
+        })?;
+        known.insert(
+            (String::from(parts[0]), part),
+            parts[2].replace("\\n", "\n"),
+        );
+    }
+
+    Ok(known)
+}
+
+pub fn check<'a>(
+    known: &'a HashMap<(String, u8), String>,
+    input: &str,
+    answer: &'a Answer,
+) -> Verdict<'a> {
+    let value = match &answer.value {
+        Ok(value) => value,
+        Err(err) => return Verdict::Error(err),
+    };
+    match known.get(&(String::from(input), answer.part)) {
+        Some(expected) if expected == value => Verdict::Pass(value),
+        Some(expected) => Verdict::Fail(value, expected),
+        None => Verdict::Unknown(value),
+    }
+}
@@ -1,61 +1,41 @@
-use std::{collections::HashMap, num::ParseIntError, str::FromStr};
+use crate::{
+    parser::{self, ParseError},
+    solution::Solution,
+};
 
-use crate::parser;
+pub struct Day01;
 
-#[derive(Debug)]
-struct Record {
-    calories: Option<u32>,
-}
-
-impl FromStr for Record {
-    type Err = ParseIntError;
+impl Solution for Day01 {
+    // one group of calories per elf
+    type Input = Vec<Vec<u32>>;
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Record {
-            calories: match s.parse() {
-                Ok(c) => Some(c),
-                Err(_) => None,
-            },
-        })
+    fn parse(raw: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+        parser::groups_from_lines(raw)
     }
-}
 
-pub fn solve() {
-    let data: Vec<Record> = parser::records_from_lines("data/day01.example");
-    println!("Example Result PART 1: {}", part1(&data));
-    println!("Example Result PART 2: {}", part2(&data));
-
-    let data: Vec<Record> = parser::records_from_lines("data/day01.txt");
-    println!("Final Result PART 1: {}", part1(&data));
-    println!("Final Result PART 2: {}", part2(&data));
-}
+    fn part1(elves: &Vec<Vec<u32>>) -> u32 {
+        part1(elves)
+    }
 
-fn make_elves(data: &Vec<Record>) -> HashMap<usize, u32> {
-    let mut elf_calories = HashMap::new();
-    let mut elf: usize = 0;
-
-    for d in data {
-        match d.calories {
-            Some(c) => {
-                let cal_count = elf_calories.entry(elf).or_insert(0u32);
-                *cal_count += c;
-            }
-            None => elf += 1,
-        }
+    fn part2(elves: &Vec<Vec<u32>>) -> u32 {
+        part2(elves)
     }
-    elf_calories
 }
 
-fn part1(data: &Vec<Record>) -> u32 {
-    let elf_calories = make_elves(data);
-    let mut calorie_list: Vec<u32> = elf_calories.values().cloned().collect();
+fn elf_calories(elves: &[Vec<u32>]) -> Vec<u32> {
+    let mut calorie_list: Vec<u32> = elves.iter().map(|elf| elf.iter().sum()).collect();
     calorie_list.sort_by(|a, b| b.cmp(a));
+    calorie_list
+}
+
+fn part1(elves: &[Vec<u32>]) -> u32 {
+    let calorie_list = elf_calories(elves);
     calorie_list[0]
 }
 
-fn part2(data: &Vec<Record>) -> u32 {
-    let elf_calories = make_elves(data);
-    let mut calorie_list: Vec<u32> = elf_calories.values().cloned().collect();
-    calorie_list.sort_by(|a, b| b.cmp(a));
+fn part2(elves: &[Vec<u32>]) -> u32 {
+    let calorie_list = elf_calories(elves);
     calorie_list[0] + calorie_list[1] + calorie_list[2]
 }
@@ -1,5 +1,8 @@
-use crate::parser;
-use std::{num::ParseIntError, str::FromStr};
+use crate::{
+    parser::{self, ParseError, RecordError},
+    solution::Solution,
+};
+use std::str::FromStr;
 use strum_macros::EnumString;
 
 #[derive(Debug, Clone, PartialEq, EnumString)]
@@ -23,13 +26,13 @@ enum RoundResult {
 }
 
 #[derive(Debug)]
-struct RecordPart1 {
+pub struct RecordPart1 {
     opponent: Shape,
     response: Shape,
 }
 
 #[derive(Debug)]
-struct RecordPart2 {
+pub struct RecordPart2 {
     opponent: Shape,
     response: RoundResult,
 }
@@ -65,31 +68,31 @@ impl RoundResult {
 }
 
 impl FromStr for RecordPart1 {
-    type Err = ParseIntError;
+    type Err = RecordError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split(" ");
-        let parts: Vec<&str> = parts.collect();
+        let opponent = parser::field(s, 0, "opponent")?;
+        let response = parser::field(s, 1, "response")?;
         Ok(RecordPart1 {
-            opponent: Shape::from_str(parts.get(0).expect("bad opponent").trim())
-                .expect("invalid shape (ABC)"),
-            response: Shape::from_str(parts.get(1).expect("bad response").trim())
-                .expect("invalid shape (XYZ)"),
+            opponent: Shape::from_str(opponent)
+                .map_err(|_err| RecordError(format!("invalid shape {:?} (ABC)", opponent)))?,
+            response: Shape::from_str(response)
+                .map_err(|_err| RecordError(format!("invalid shape {:?} (XYZ)", response)))?,
         })
     }
 }
 
 impl FromStr for RecordPart2 {
-    type Err = ParseIntError;
+    type Err = RecordError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split(" ");
-        let parts: Vec<&str> = parts.collect();
+        let opponent = parser::field(s, 0, "opponent")?;
+        let response = parser::field(s, 1, "response")?;
         Ok(RecordPart2 {
-            opponent: Shape::from_str(parts.get(0).expect("bad opponent").trim())
-                .expect("invalid part 2 shape (ABC)"),
-            response: RoundResult::from_str(parts.get(1).expect("bad response").trim())
-                .expect("invalid response (XYZ)"),
+            opponent: Shape::from_str(opponent)
+                .map_err(|_err| RecordError(format!("invalid shape {:?} (ABC)", opponent)))?,
+            response: RoundResult::from_str(response)
+                .map_err(|_err| RecordError(format!("invalid response {:?} (XYZ)", response)))?,
         })
     }
 }
@@ -150,7 +153,7 @@ impl Round for RecordPart2 {
     }
 }
 
-fn sum_rounds<T>(data: &Vec<T>) -> u32
+fn sum_rounds<T>(data: &[T]) -> u32
 where
     T: Round,
 {
@@ -161,22 +164,25 @@ where
     sum
 }
 
-fn part1(filename: &str) -> u32 {
-    let data: Vec<RecordPart1> = parser::records_from_lines(filename);
-    sum_rounds(&data)
-}
+pub struct Day02;
 
-fn part2(filename: &str) -> u32 {
-    let data: Vec<RecordPart2> = parser::records_from_lines(filename);
-    sum_rounds(&data)
-}
+impl Solution for Day02 {
+    type Input = (Vec<RecordPart1>, Vec<RecordPart2>);
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(raw: &str) -> Result<Self::Input, ParseError> {
+        Ok((
+            parser::records_from_lines(raw)?,
+            parser::records_from_lines(raw)?,
+        ))
+    }
 
-pub fn solve() {
-    let filename = "data/day02.example";
-    println!("Example Result DAY 02 PART 1: {}", part1(filename));
-    println!("Example Result DAY 02 PART 2: {}", part2(filename));
+    fn part1(data: &Self::Input) -> u32 {
+        sum_rounds(&data.0)
+    }
 
-    let filename = "data/day02.txt";
-    println!("Final Result DAY 02 PART 1: {}", part1(filename));
-    println!("Final Result DAY 02 PART 2: {}", part2(filename));
+    fn part2(data: &Self::Input) -> u32 {
+        sum_rounds(&data.1)
+    }
 }
@@ -1,15 +1,25 @@
-use std::fs;
+use crate::{parser::ParseError, solution::Solution};
 
 const PRIORITIES: &str = ".abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day03.example").unwrap();
-    println!("Example Result DAY 03 PART 1: {}", part1(&raw));
-    println!("Example Result DAY 03 PART 1: {}", part2(&raw));
+pub struct Day03;
 
-    let raw = fs::read_to_string("data/day03.txt").unwrap();
-    println!("Final Result DAY 03 PART 1: {}", part1(&raw));
-    println!("Final Result DAY 03 PART 1: {}", part2(&raw));
+impl Solution for Day03 {
+    type Input = String;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(raw: &str) -> Result<String, ParseError> {
+        Ok(raw.to_string())
+    }
+
+    fn part1(raw: &String) -> u32 {
+        part1(raw)
+    }
+
+    fn part2(raw: &String) -> u32 {
+        part2(raw)
+    }
 }
 
 fn part1(raw: &str) -> u32 {
@@ -47,7 +57,7 @@ fn part2(raw: &str) -> u32 {
     priority_sum
 }
 
-fn find_common(group: &Vec<&str>) -> char {
+fn find_common(group: &[&str]) -> char {
     for char in group[0].chars() {
         if group[1].contains(char) && group[2].contains(char) {
             return char;
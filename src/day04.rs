@@ -1,5 +1,8 @@
-use crate::parser;
-use std::{num::ParseIntError, str::FromStr};
+use crate::{
+    parser::{self, ParseError, RecordError},
+    solution::Solution,
+};
+use std::str::FromStr;
 
 #[derive(Debug)]
 struct ElfRange {
@@ -8,43 +11,62 @@ struct ElfRange {
 }
 
 #[derive(Debug)]
-struct Record {
+pub struct Record {
     first: ElfRange,
     second: ElfRange,
 }
 
 impl FromStr for Record {
-    type Err = ParseIntError;
+    type Err = RecordError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let elfs: Vec<&str> = s.split(",").collect();
-        let elf1_range: Vec<&str> = elfs[0].split("-").collect();
-        let elf2_range: Vec<&str> = elfs[1].split("-").collect();
+        let (elf1, elf2) = s
+            .split_once(',')
+            .ok_or_else(|| RecordError(String::from("expected two ranges")))?;
 
         Ok(Record {
-            first: ElfRange {
-                start: elf1_range[0].parse().unwrap(),
-                end: elf1_range[1].parse().unwrap(),
-            },
-            second: ElfRange {
-                start: elf2_range[0].parse().unwrap(),
-                end: elf2_range[1].parse().unwrap(),
-            },
+            first: elf1.parse()?,
+            second: elf2.parse()?,
         })
     }
 }
 
-pub fn solve() {
-    let data: Vec<Record> = parser::records_from_lines("data/day04.example");
-    println!("Example Result PART 1: {}", part1(&data));
-    println!("Example Result PART 2: {}", part2(&data));
+impl FromStr for ElfRange {
+    type Err = RecordError;
 
-    let data: Vec<Record> = parser::records_from_lines("data/day04.txt");
-    println!("Final Result PART 1: {}", part1(&data));
-    println!("Final Result PART 2: {}", part2(&data));
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| RecordError(format!("expected a range, got {:?}", s)))?;
+
+        Ok(ElfRange {
+            start: start.parse()?,
+            end: end.parse()?,
+        })
+    }
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<Record>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(raw: &str) -> Result<Vec<Record>, ParseError> {
+        parser::records_from_lines(raw)
+    }
+
+    fn part1(data: &Vec<Record>) -> u32 {
+        part1(data)
+    }
+
+    fn part2(data: &Vec<Record>) -> u32 {
+        part2(data)
+    }
 }
 
-fn part1(data: &Vec<Record>) -> u32 {
+fn part1(data: &[Record]) -> u32 {
     let mut sum: u32 = 0;
     for pair in data {
         if (pair.first.start >= pair.second.start && pair.first.end <= pair.second.end)
@@ -56,7 +78,7 @@ fn part1(data: &Vec<Record>) -> u32 {
     sum
 }
 
-fn part2(data: &Vec<Record>) -> u32 {
+fn part2(data: &[Record]) -> u32 {
     let mut sum: u32 = 0;
     for pair in data {
         if (pair.first.end < pair.second.end || pair.first.start > pair.second.end)
@@ -1,28 +1,57 @@
-use std::fs;
+use std::str::FromStr;
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day05.example").unwrap();
-    println!("Example Result PART 1: {}", part1(&raw));
-    println!("Example Result PART 2: {}", part2(&raw));
+use crate::{
+    parser::{self, ParseError, RecordError},
+    solution::Solution,
+};
 
-    let raw = fs::read_to_string("data/day05.txt").unwrap();
-    println!("Final Result PART 1: {}", part1(&raw));
-    println!("Final Result PART 2: {}", part2(&raw));
+type Stacks = Vec<Vec<char>>;
+
+#[derive(Debug)]
+pub struct Command {
+    count: u32,
+    from: usize,
+    to: usize,
+}
+
+impl FromStr for Command {
+    type Err = RecordError;
+
+    // move 1 from 2 to 1
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Command {
+            count: parser::field(s, 1, "count")?.parse()?,
+            from: parser::field(s, 3, "from stack")?.parse()?,
+            to: parser::field(s, 5, "to stack")?.parse()?,
+        })
+    }
 }
 
-fn part1(raw: &str) -> String {
-    let (stacks, commands) = parse(raw);
-    let mut stacks = stacks;
-    let stack_count = stacks.len();
+pub struct Day05;
 
-    for command in commands.lines() {
-        let raw: Vec<&str> = command.split(" ").collect();
-        let (count, from, to): (u32, usize, usize) = (
-            raw[1].parse().unwrap(),
-            raw[3].parse().unwrap(),
-            raw[5].parse().unwrap(),
-        );
+impl Solution for Day05 {
+    type Input = (Stacks, Vec<Command>);
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(raw: &str) -> Result<Self::Input, ParseError> {
+        parse(raw)
+    }
+
+    fn part1(input: &Self::Input) -> String {
+        part1(input)
+    }
 
+    fn part2(input: &Self::Input) -> String {
+        part2(input)
+    }
+}
+
+fn part1(input: &(Stacks, Vec<Command>)) -> String {
+    let (stacks, commands) = input;
+    let mut stacks = stacks.clone();
+
+    for &Command { count, from, to } in commands {
         for _ in 0..count {
             let moving = stacks[from - 1].pop().unwrap();
             stacks[to - 1].push(moving);
@@ -30,27 +59,19 @@ fn part1(raw: &str) -> String {
     }
 
     let mut tops = Vec::new();
-    for i in 0..stack_count {
-        tops.push(String::from(stacks[i as usize].pop().unwrap()));
+    for stack in stacks.iter_mut() {
+        tops.push(String::from(stack.pop().unwrap()));
     }
 
     tops.join("")
 }
 
-fn part2(raw: &str) -> String {
-    let (stacks, commands) = parse(raw);
-    let mut stacks = stacks;
-    let stack_count = stacks.len();
+fn part2(input: &(Stacks, Vec<Command>)) -> String {
+    let (stacks, commands) = input;
+    let mut stacks = stacks.clone();
     let mut reshuffler = Vec::new();
 
-    for command in commands.lines() {
-        let raw: Vec<&str> = command.split(" ").collect();
-        let (count, from, to): (u32, usize, usize) = (
-            raw[1].parse().unwrap(),
-            raw[3].parse().unwrap(),
-            raw[5].parse().unwrap(),
-        );
-
+    for &Command { count, from, to } in commands {
         for _ in 0..count {
             reshuffler.push(stacks[from - 1].pop().unwrap());
         }
@@ -60,26 +81,38 @@ fn part2(raw: &str) -> String {
     }
 
     let mut tops = Vec::new();
-    for i in 0..stack_count {
-        tops.push(String::from(stacks[i as usize].pop().unwrap()));
+    for stack in stacks.iter_mut() {
+        tops.push(String::from(stack.pop().unwrap()));
     }
 
     tops.join("")
 }
 
-fn parse(raw: &str) -> (Vec<Vec<char>>, &str) {
-    let parts: Vec<&str> = raw.split("\n\n").collect();
-    let (stacks, commands) = (parts[0], parts[1]);
+fn parse(raw: &str) -> Result<(Stacks, Vec<Command>), ParseError> {
+    let blocks = parser::blocks(raw);
+    let [stacks, commands] = &blocks[..] else {
+        return Err(ParseError::new(
+            1,
+            raw.lines().next().unwrap_or_default(),
+            RecordError(format!(
+                "expected stacks and commands, got {} blocks",
+                blocks.len()
+            )),
+        ));
+    };
+
+    Ok((stacks.parse_with(parse_stacks)?, commands.records()?))
+}
+
+fn parse_stacks(stacks: &str) -> Result<Stacks, RecordError> {
     let stacks_reversed: Vec<&str> = stacks.lines().rev().collect();
     let stack_count: u8 = stacks_reversed[0]
-        .trim()
-        .split(" ")
-        .last()
-        .unwrap()
-        .parse()
-        .unwrap();
-
-    let mut stacks: Vec<Vec<char>> = Vec::new();
+        .split_whitespace()
+        .next_back()
+        .ok_or_else(|| RecordError(String::from("missing stack numbers")))?
+        .parse()?;
+
+    let mut stacks: Stacks = Vec::new();
     for _ in 0..stack_count {
         stacks.push(Vec::new());
     }
@@ -88,7 +121,8 @@ fn parse(raw: &str) -> (Vec<Vec<char>>, &str) {
         let line_chars: Vec<char> = l.chars().collect();
         for i in 0..stack_count {
             let pos = (i as usize) * 4 + 1;
-            let pos_char = line_chars[pos];
+            // trailing empty stacks can be trimmed off the end of the line
+            let pos_char = line_chars.get(pos).copied().unwrap_or(' ');
             if pos_char == ' ' {
                 continue;
             }
@@ -96,5 +130,5 @@ fn parse(raw: &str) -> (Vec<Vec<char>>, &str) {
         }
     }
 
-    (stacks, commands)
+    Ok(stacks)
 }
@@ -1,17 +1,36 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fs,
-};
-
-pub fn solve() {
-    let raw = fs::read_to_string("data/day06.examples").unwrap();
-    for line in raw.lines() {
-        println!("{}: start-of-packet: {}", line, for_line(&line, 4));
-        println!("{}: start-of-message: {}", line, for_line(&line, 14));
+use std::collections::{HashSet, VecDeque};
+
+use crate::{parser::ParseError, solution::Solution};
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<String>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    const INPUTS: &'static [&'static str] = &["examples", "txt"];
+
+    fn parse(raw: &str) -> Result<Vec<String>, ParseError> {
+        Ok(raw.lines().map(String::from).collect())
     }
-    let raw = fs::read_to_string("data/day06.txt").unwrap();
-    println!("Final result start-of-packet:  {}", for_line(&raw, 4));
-    println!("Final result start-of-message: {}", for_line(&raw, 14));
+
+    fn part1(streams: &Vec<String>) -> String {
+        for_streams(streams, 4)
+    }
+
+    fn part2(streams: &Vec<String>) -> String {
+        for_streams(streams, 14)
+    }
+}
+
+// the examples file has one stream per line, so answer them all at once
+fn for_streams(streams: &[String], buffer_length: usize) -> String {
+    streams
+        .iter()
+        .map(|stream| for_line(stream, buffer_length).to_string())
+        .collect::<Vec<String>>()
+        .join(",")
 }
 
 fn for_line(stream: &str, buffer_length: usize) -> usize {
@@ -25,7 +44,7 @@ fn for_line(stream: &str, buffer_length: usize) -> usize {
 
         validator.clear();
         for sc in &buffer {
-            validator.insert(sc.clone());
+            validator.insert(*sc);
         }
 
         // println!("Considering {:?}: {}", buffer, validator.len());
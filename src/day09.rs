@@ -1,9 +1,11 @@
-use crate::parser;
-use std::{
-    collections::{HashMap, HashSet},
-    num::ParseIntError,
-    str::FromStr,
+use crate::{
+    draw::Frames,
+    geometry::{Aabb, Vec2},
+    grid::Grid,
+    parser::{self, ParseError, RecordError},
+    solution::Solution,
 };
+use std::{collections::HashSet, str::FromStr};
 use strum_macros::EnumString;
 
 #[derive(Debug, Clone, PartialEq, EnumString)]
@@ -19,161 +21,109 @@ enum Direction {
 }
 
 #[derive(Debug)]
-struct Move {
+pub struct Move {
     direction: Direction,
     amount: u8,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Vector2 {
-    x: i32,
-    y: i32,
-}
-
 impl FromStr for Move {
-    type Err = ParseIntError;
+    type Err = RecordError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split(" ");
-        let parts: Vec<&str> = parts.collect();
+        let direction = parser::field(s, 0, "move direction")?;
         Ok(Move {
-            direction: Direction::from_str(parts.get(0).expect("bad move direction").trim())
-                .expect("invalid direction"),
-            amount: parts
-                .get(1)
-                .expect("bad move amount")
-                .trim()
-                .parse()
-                .expect("invalid amount"),
+            direction: Direction::from_str(direction)
+                .map_err(|_err| RecordError(format!("invalid direction {:?}", direction)))?,
+            amount: parser::field(s, 1, "move amount")?.parse()?,
         })
     }
 }
 
-impl Vector2 {
-    fn new() -> Vector2 {
-        Vector2 { x: 0, y: 0 }
+// pull a knot along behind the one in front of it, a step at most in each axis
+fn follow(knot: &mut Vec2, leader: Vec2) {
+    if knot.chebyshev(leader) > 1 {
+        *knot += (leader - *knot).signum();
     }
+}
 
-    fn from(x: i32, y: i32) -> Vector2 {
-        Vector2 { x, y }
+impl Direction {
+    // y points up here, unlike on the grid maps
+    fn unit_vector(&self) -> Vec2 {
+        match self {
+            Direction::Down => Vec2::new(0, -1),
+            Direction::Up => Vec2::new(0, 1),
+            Direction::Left => Vec2::LEFT,
+            Direction::Right => Vec2::RIGHT,
+        }
     }
+}
 
-    fn move_by(&mut self, other: &Vector2) {
-        self.x += other.x;
-        self.y += other.y;
-    }
+pub struct Day09;
 
-    fn follow(&mut self, other: &Vector2) {
-        if self.touching(other) {
-            return ();
-        }
-        let delta = other.subtract(self);
-        if delta.x == 0 {
-            // follow in y
-            if delta.y > 0 {
-                self.move_by(&Vector2::from(0, 1));
-            } else {
-                self.move_by(&Vector2::from(0, -1));
-            }
-        } else if delta.y == 0 {
-            // follow in x
-            if delta.x > 0 {
-                self.move_by(&Vector2::from(1, 0));
-            } else {
-                self.move_by(&Vector2::from(-1, 0));
-            }
-        } else {
-            let vx = if delta.x > 0 { 1 } else { -1 };
-            let vy = if delta.y > 0 { 1 } else { -1 };
-            self.move_by(&Vector2::from(vx, vy));
-        }
-    }
+impl Solution for Day09 {
+    type Input = Vec<Move>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    fn touching(&self, other: &Vector2) -> bool {
-        let touching_vectors = [
-            Vector2::from(-1, -1),
-            Vector2::from(-1, 0),
-            Vector2::from(-1, 1),
-            Vector2::from(0, -1),
-            Vector2::from(0, 0),
-            Vector2::from(0, 1),
-            Vector2::from(1, -1),
-            Vector2::from(1, 0),
-            Vector2::from(1, 1),
-        ];
-        for v in touching_vectors {
-            if self.add(&v) == *other {
-                return true;
-            }
-        }
-        false
-    }
+    const INPUTS: &'static [&'static str] = &["example", "example2", "txt"];
 
-    fn add(&self, other: &Vector2) -> Vector2 {
-        Vector2 {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
+    fn parse(raw: &str) -> Result<Vec<Move>, ParseError> {
+        parser::records_from_lines(raw)
     }
 
-    fn subtract(&self, other: &Vector2) -> Vector2 {
-        Vector2 {
-            x: self.x - other.x,
-            y: self.y - other.y,
-        }
+    fn part1(data: &Vec<Move>) -> usize {
+        visit(data, 2, &mut |_rope, _visited| {}).len()
     }
-}
 
-impl Direction {
-    fn unit_vector(&self) -> Vector2 {
-        match self {
-            Direction::Down => Vector2::from(0, -1),
-            Direction::Up => Vector2::from(0, 1),
-            Direction::Left => Vector2::from(-1, 0),
-            Direction::Right => Vector2::from(1, 0),
-        }
+    fn part2(data: &Vec<Move>) -> usize {
+        visit(data, 10, &mut |_rope, _visited| {}).len()
     }
-}
-
-pub fn solve() {
-    part1("data/day09.example", false);
-    part1("data/day09.txt", false);
-    part2("data/day09.example2", false);
-    part2("data/day09.txt", false);
-}
 
-fn part1(filename: &str, show_steps: bool) {
-    let data: Vec<Move> = parser::records_from_lines(filename);
-    let visited = visit(&data, 2, show_steps);
-    println!("Part 1: {}", visited.len());
-}
+    // where the tail has been, and the rope where it ends up
+    fn draw(data: &Vec<Move>, part: u8) -> Option<Grid<char>> {
+        let rope_size = if part == 1 { 2 } else { 10 };
+        let window = rope_window(data, rope_size);
+        let mut rope = Vec::new();
+        let visited = visit(data, rope_size, &mut |knots, _visited| {
+            rope = knots.to_vec()
+        });
+        Some(draw_rope(&rope, &visited, window))
+    }
 
-fn part2(filename: &str, show_steps: bool) {
-    let data: Vec<Move> = parser::records_from_lines(filename);
-    let visited = visit(&data, 10, show_steps);
-    println!("Part 1: {}", visited.len());
+    // a frame for each step the head takes
+    fn animate(data: &Vec<Move>, part: u8, frames: &mut Frames) {
+        let rope_size = if part == 1 { 2 } else { 10 };
+        let window = rope_window(data, rope_size);
+        visit(data, rope_size, &mut |rope, visited| {
+            frames.step(&[("tail visited", visited.len())], || {
+                draw_rope(rope, visited, window)
+            })
+        });
+    }
 }
 
-fn visit(data: &Vec<Move>, rope_size: usize, show_steps: bool) -> HashSet<Vector2> {
+// the cells the tail visits. `on_step` sees the rope and where the tail has been so far,
+// before the first step and after every one.
+fn visit(
+    data: &[Move],
+    rope_size: usize,
+    on_step: &mut dyn FnMut(&[Vec2], &HashSet<Vec2>),
+) -> HashSet<Vec2> {
     let mut visited = HashSet::new();
-    let mut rope = Vec::new();
+    let mut rope = vec![Vec2::ZERO; rope_size];
 
-    for _ in 0..rope_size {
-        rope.push(Vector2::new());
-    }
-
-    visited.insert(Vector2::from(0, 0));
+    visited.insert(Vec2::ZERO);
+    on_step(&rope, &visited);
 
     for move_ in data {
         // println!("{:?}", move_);
         for _ in 0..move_.amount {
-            rope[0].move_by(&move_.direction.unit_vector());
+            rope[0] += move_.direction.unit_vector();
             for i in 1..rope_size {
-                let leader = rope[i - 1].clone();
-                rope[i].follow(&leader);
+                let leader = rope[i - 1];
+                follow(&mut rope[i], leader);
             }
-            let tail = rope.last().unwrap();
-            visited.insert(Vector2::from(tail.x, tail.y));
+            visited.insert(*rope.last().unwrap());
 
             // println!(
             //     ".. move 1 {:?}: H={:?} T={:?} T visited: {}",
@@ -182,33 +132,45 @@ fn visit(data: &Vec<Move>, rope_size: usize, show_steps: bool) -> HashSet<Vector
             //     tail,
             //     visited.len()
             // );
-            if show_steps {
-                show_state(&rope)
-            }
+            on_step(&rope, &visited);
         }
     }
 
     visited
 }
 
-fn show_state(rope: &Vec<Vector2>) {
-    let mut rope_map = HashMap::new();
-    // reverse so that the end of the rope is covered
-    for (i, knot) in rope.iter().enumerate().rev() {
-        rope_map.insert(Vector2::from(knot.x, knot.y), i);
-    }
+// everywhere the rope goes, with a cell to spare around it
+fn rope_window(data: &[Move], rope_size: usize) -> Aabb<2> {
+    let mut window = Aabb::around(Vec2::ZERO);
+    visit(data, rope_size, &mut |rope, _visited| {
+        for &knot in rope {
+            window.grow(knot);
+        }
+    });
+    window.expand(1)
+}
 
-    for j in (-5..15).rev() {
-        for i in -11..14 {
-            let v = rope_map.get(&Vector2::from(i, j));
-            match v {
-                Some(0) => print!("H"),
-                Some(10) => print!("T"),
-                Some(i) => print!("{}", i),
-                None => print!("."),
-            }
+// the rope as the puzzle draws it, over the cells in `visited`, with y turned back round
+// so that up is up
+fn draw_rope(rope: &[Vec2], visited: &HashSet<Vec2>, bounds: Aabb<2>) -> Grid<char> {
+    let [width, height] = bounds.size();
+    let mut picture = Grid::new(width as usize, height as usize, '.');
+    let to_cell = |pos: Vec2| Vec2::new(pos.x - bounds.min[0], bounds.max[1] - pos.y);
+
+    for &pos in visited {
+        if let Some(cell) = picture.get_mut(to_cell(pos)) {
+            *cell = '#';
+        }
+    }
+    // the end of the rope first, so the knots in front of it cover it
+    for (i, &knot) in rope.iter().enumerate().rev() {
+        if let Some(cell) = picture.get_mut(to_cell(knot)) {
+            *cell = match i {
+                0 => 'H',
+                _ if rope.len() == 2 => 'T',
+                i => char::from_digit(i as u32, 10).unwrap_or('T'),
+            };
         }
-        println!();
     }
-    println!();
+    picture
 }
@@ -1,14 +1,17 @@
-use crate::parser;
-use std::{fs, num::ParseIntError, str::FromStr};
+use crate::{
+    parser::{self, ParseError, RecordError},
+    solution::Solution,
+};
+use std::str::FromStr;
 
 #[derive(Debug)]
 enum Keyword {
-    noop,
-    addx,
+    Noop,
+    Addx,
 }
 
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     keyword: Keyword,
     value: Option<i32>,
     cycles: i32,
@@ -16,54 +19,66 @@ struct Instruction {
 
 #[derive(Debug)]
 struct State {
-    X: i32,
+    x: i32,
 }
 
 impl FromStr for Instruction {
-    type Err = ParseIntError;
+    type Err = RecordError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = line.split(" ").collect();
-        if parts.len() == 1 {
-            return Ok(Instruction {
-                keyword: Keyword::noop,
+        match parser::field(line, 0, "instruction")? {
+            "noop" => Ok(Instruction {
+                keyword: Keyword::Noop,
                 value: None,
                 cycles: 1,
-            });
+            }),
+            "addx" => Ok(Instruction {
+                keyword: Keyword::Addx,
+                value: Some(parser::field(line, 1, "addx value")?.parse()?),
+                cycles: 2,
+            }),
+            keyword => Err(RecordError(format!("unknown instruction {:?}", keyword))),
         }
-        Ok(Instruction {
-            keyword: Keyword::addx,
-            value: Some(parts[1].parse().unwrap()),
-            cycles: 2,
-        })
     }
 }
 
 impl Instruction {
     fn end_cycle(&self, cycle: i32, state: &mut State) {
-        match self.keyword {
-            Keyword::addx => {
-                if cycle == self.cycles {
-                    state.X += self.value.unwrap();
-                }
+        if let Keyword::Addx = self.keyword {
+            if cycle == self.cycles {
+                state.x += self.value.unwrap();
             }
-            _ => (),
         }
     }
 }
 
-pub fn solve() {
-    let program: Vec<Instruction> = parser::records_from_lines("data/day10.example2");
-    part1(&program);
+pub struct Day10;
 
-    let program: Vec<Instruction> = parser::records_from_lines("data/day10.txt");
-    part1(&program);
+impl Solution for Day10 {
+    type Input = Vec<Instruction>;
+    type Answer1 = i32;
+    type Answer2 = String;
+
+    const INPUTS: &'static [&'static str] = &["example2", "txt"];
+
+    fn parse(raw: &str) -> Result<Vec<Instruction>, ParseError> {
+        parser::records_from_lines(raw)
+    }
+
+    fn part1(program: &Vec<Instruction>) -> i32 {
+        execute(program).0
+    }
+
+    fn part2(program: &Vec<Instruction>) -> String {
+        execute(program).1
+    }
 }
 
-fn part1(program: &Vec<Instruction>) {
-    let mut state = State { X: 1 };
+fn execute(program: &[Instruction]) -> (i32, String) {
+    let mut state = State { x: 1 };
     let mut cycle = 0;
     let mut signal_strength = Vec::new();
+    let mut screen = String::new();
 
     for inst in program {
         for inst_cycle in 0..inst.cycles {
@@ -73,18 +88,18 @@ fn part1(program: &Vec<Instruction>) {
             let is_interesting = ((cycle + 20) % 40) == 0;
             if is_interesting {
                 // println!("During {}: {:?} | {:?}", cycle, inst, state);
-                signal_strength.push(state.X * cycle);
+                signal_strength.push(state.x * cycle);
             }
 
             let cursor = (cycle - 1) % 40;
-            if state.X - 1 == cursor || state.X == cursor || state.X + 1 == cursor {
-                print!("#");
+            if state.x - 1 == cursor || state.x == cursor || state.x + 1 == cursor {
+                screen.push('#');
             } else {
-                print!(".");
+                screen.push('.');
             }
 
             if cycle % 40 == 0 {
-                println!();
+                screen.push('\n');
             }
 
             inst.end_cycle(inst_cycle + 1, &mut state);
@@ -92,5 +107,8 @@ fn part1(program: &Vec<Instruction>) {
         }
     }
 
-    println!("Part 1: {}", signal_strength.iter().sum::<i32>());
+    (
+        signal_strength.iter().sum::<i32>(),
+        screen.trim_end().to_string(),
+    )
 }
@@ -1,4 +1,9 @@
-use std::{collections::HashMap, fs};
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    parser::{self, ParseError, RecordError},
+    solution::Solution,
+};
 
 #[derive(Debug, Clone)]
 enum Operator {
@@ -7,7 +12,7 @@ enum Operator {
 }
 
 #[derive(Debug, Clone)]
-struct Monkey {
+pub struct Monkey {
     items: Vec<u128>,
     operator: Operator,
     operation_value: Option<u128>,
@@ -17,38 +22,37 @@ struct Monkey {
     inspection_count: u128,
 }
 
-impl Monkey {
-    fn make(monkey_raw: &str) -> Monkey {
+impl FromStr for Monkey {
+    type Err = RecordError;
+
+    fn from_str(monkey_raw: &str) -> Result<Self, Self::Err> {
         let lines: Vec<&str> = monkey_raw.lines().collect();
-        let start_items: Vec<u128> = strip_label(lines[1])
+        let line = |i: usize| {
+            lines
+                .get(i)
+                .copied()
+                .ok_or_else(|| RecordError(format!("expected 6 lines, got {}", lines.len())))
+        };
+
+        let start_items = strip_label(line(1)?)?
             .split(", ")
-            .map(|s: &str| s.parse::<u128>().unwrap())
-            .collect();
+            .map(|s: &str| s.parse::<u128>())
+            .collect::<Result<Vec<u128>, _>>()?;
 
-        let op_raw: Vec<&str> = strip_label(lines[2]).split(" ").collect();
-        let operator = match op_raw[3] {
+        // new = old * 19
+        let operation = strip_label(line(2)?)?;
+        let operator = match parser::field(operation, 3, "operator")? {
             "+" => Operator::Add,
             "*" => Operator::Mul,
-            _ => panic!("not an operation operator"),
-        };
-        let operation_value: Option<u128> = match op_raw[4].parse() {
-            Ok(v) => Some(v),
-            Err(_) => None,
+            op => return Err(RecordError(format!("not an operation operator: {}", op))),
         };
+        let operation_value: Option<u128> = parser::field(operation, 4, "operand")?.parse().ok();
 
-        let test_divisor: u128 = strip_label(lines[3]).split(" ").collect::<Vec<&str>>()[2]
-            .parse()
-            .unwrap();
+        let test_divisor: u128 = parser::field(strip_label(line(3)?)?, 2, "divisor")?.parse()?;
+        let monkey_true: usize = parser::field(strip_label(line(4)?)?, 3, "monkey")?.parse()?;
+        let monkey_false: usize = parser::field(strip_label(line(5)?)?, 3, "monkey")?.parse()?;
 
-        let monkey_true: usize = strip_label(lines[4]).split(" ").collect::<Vec<&str>>()[3]
-            .parse()
-            .unwrap();
-
-        let monkey_false: usize = strip_label(lines[5]).split(" ").collect::<Vec<&str>>()[3]
-            .parse()
-            .unwrap();
-
-        Monkey {
+        Ok(Monkey {
             items: start_items,
             operator,
             operation_value,
@@ -56,32 +60,25 @@ impl Monkey {
             monkey_true,
             monkey_false,
             inspection_count: 0,
-        }
+        })
     }
 }
 
-fn make_monkeys(monkeys_string: &str) -> Vec<Monkey> {
-    let mut monkeys = Vec::new();
-
-    for monkey_raw in monkeys_string.split("\n\n") {
-        monkeys.push(Monkey::make(monkey_raw));
-    }
-
-    monkeys
-}
-
-fn strip_label(value: &str) -> &str {
-    value.split(": ").collect::<Vec<&str>>()[1]
+fn strip_label(value: &str) -> Result<&str, RecordError> {
+    value
+        .split_once(": ")
+        .map(|(_label, value)| value)
+        .ok_or_else(|| RecordError(format!("expected a label in {:?}", value.trim())))
 }
 
-fn part(part_num: u8, raw: &str, relieved: bool, round_count: i32) {
-    let mut monkeys = make_monkeys(&raw);
+fn part(monkeys: &[Monkey], relieved: bool, round_count: i32) -> u128 {
+    let mut monkeys = monkeys.to_vec();
     // println!("{:?}", monkeys);
     let mut tossing: HashMap<usize, Vec<u128>> = HashMap::new();
     let ring_size = monkeys.iter().fold(1u128, |a, b| a * b.test_divisor);
 
     // rounds
-    for round in 0..round_count {
+    for _round in 0..round_count {
         for monkey_index in 0..monkeys.len() {
             // for current_monkey in monkeys.iter_mut() {
             let current_monkey = &mut monkeys[monkey_index];
@@ -111,19 +108,16 @@ fn part(part_num: u8, raw: &str, relieved: bool, round_count: i32) {
                     true => current_monkey.monkey_true,
                     false => current_monkey.monkey_false,
                 };
-                tossing.entry(push_index).or_insert(Vec::new()).push(worry);
+                tossing.entry(push_index).or_default().push(worry);
             }
             current_monkey.items.clear();
 
             // This rearranges the order of things. If that turns out to matter, change to a deque.
-            for toss_index in 0..monkeys.len() {
-                match tossing.get_mut(&toss_index) {
-                    Some(tosses) => {
-                        while let Some(toss_val) = tosses.pop() {
-                            monkeys[toss_index].items.push(toss_val);
-                        }
+            for (toss_index, monkey) in monkeys.iter_mut().enumerate() {
+                if let Some(tosses) = tossing.get_mut(&toss_index) {
+                    while let Some(toss_val) = tosses.pop() {
+                        monkey.items.push(toss_val);
                     }
-                    None => (),
                 }
             }
             tossing.clear();
@@ -141,15 +135,25 @@ fn part(part_num: u8, raw: &str, relieved: bool, round_count: i32) {
         .collect::<Vec<u128>>();
     inspections.sort_by(|a, b| b.cmp(a));
 
-    println!("Part {}: {}", part_num, inspections[0] * inspections[1]);
+    inspections[0] * inspections[1]
 }
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day11.example").unwrap();
-    part(1, &raw, true, 20);
-    part(2, &raw, false, 10_000);
+pub struct Day11;
 
-    let raw = fs::read_to_string("data/day11.txt").unwrap();
-    part(1, &raw, true, 20);
-    part(2, &raw, false, 10_000);
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    fn parse(raw: &str) -> Result<Vec<Monkey>, ParseError> {
+        parser::records_from_blocks(raw)
+    }
+
+    fn part1(monkeys: &Vec<Monkey>) -> u128 {
+        part(monkeys, true, 20)
+    }
+
+    fn part2(monkeys: &Vec<Monkey>) -> u128 {
+        part(monkeys, false, 10_000)
+    }
 }
@@ -1,223 +1,192 @@
-use std::{
-    collections::{BinaryHeap, HashMap},
-    fs,
+use crate::{
+    geometry::Vec2,
+    grid::Grid,
+    image::Rgb,
+    parser::{ParseError, RecordError},
+    search::{self, Path},
+    solution::Solution,
 };
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Vector2 {
-    x: i32,
-    y: i32,
+#[derive(Debug)]
+pub struct HeightMap {
+    heights: Grid<i32>,
+    start: Vec2,
+    end: Vec2,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct VectorHeuristic {
-    from: Vector2,
-    to: Vector2,
-    distance_to_end: u32,
-}
-
-impl Vector2 {
-    fn add(&self, other: Vector2) -> Vector2 {
-        Vector2 {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
-    }
-
-    fn distance(&self, other: Vector2) -> u32 {
-        (self.x - other.x).abs() as u32 + (self.y - other.y).abs() as u32
+// any size of map will do, as long as every row is the same width
+fn load(raw: &str) -> Result<HeightMap, ParseError> {
+    let map = Grid::parse(raw, |c| c).map_err(|err| err.in_input(raw))?;
+
+    let line = |pos: Vec2| raw.lines().nth(pos.y as usize).unwrap_or_default();
+    if let Some((pos, c)) = map
+        .iter()
+        .find(|(_pos, c)| !matches!(c, 'a'..='z' | 'S' | 'E'))
+    {
+        return Err(ParseError::new(
+            pos.y as usize + 1,
+            line(pos),
+            RecordError(format!("{:?} in column {} isn't a height", c, pos.x + 1)),
+        ));
     }
 
-    fn neighbours(&self, width: i32, height: i32) -> [Option<Vector2>; 4] {
-        let mut neighbours = [None; 4];
-        for (i, dir) in DIRECTIONS.iter().enumerate() {
-            let potential = self.add(*dir);
-            if potential.x < 0 || potential.x >= width || potential.y < 0 || potential.y >= height {
-                continue;
-            }
-            neighbours[i] = Some(potential);
+    let find = |marker: char, name: &str| {
+        let mut found = map
+            .iter()
+            .filter(|(_pos, &c)| c == marker)
+            .map(|(pos, _c)| pos);
+        match (found.next(), found.next()) {
+            (Some(pos), None) => Ok(pos),
+            (None, _) => Err(ParseError::new(
+                1,
+                line(Vec2::ZERO),
+                RecordError(format!("no {} ({}) on the map", name, marker)),
+            )),
+            (Some(_first), Some(second)) => Err(ParseError::new(
+                second.y as usize + 1,
+                line(second),
+                RecordError(format!("a second {} ({}) on the map", name, marker)),
+            )),
         }
-        neighbours
-    }
+    };
+    let start = find('S', "start")?;
+    let end = find('E', "end")?;
+
+    let heights = map.map(|&c| match c {
+        'S' => 'a' as i32,
+        'E' => 'z' as i32,
+        val => val as i32,
+    });
+
+    Ok(HeightMap {
+        heights,
+        start,
+        end,
+    })
 }
 
-const UP: Vector2 = Vector2 { x: 0, y: -1 };
-const DOWN: Vector2 = Vector2 { x: 0, y: 1 };
-const LEFT: Vector2 = Vector2 { x: -1, y: 0 };
-const RIGHT: Vector2 = Vector2 { x: 1, y: 0 };
-const DIRECTIONS: [Vector2; 4] = [UP, RIGHT, DOWN, LEFT];
-
-fn load<const N: usize>(raw: &str, width: i32) -> ([i32; N], Vector2, Vector2) {
-    let mut height_map = [0i32; N];
-    let mut start = Vector2::default();
-    let mut end = Vector2::default();
-
-    for (j, line) in raw.lines().enumerate() {
-        for (i, col) in line.chars().enumerate() {
-            let height = match col {
-                'S' => {
-                    start.x = i as i32;
-                    start.y = j as i32;
-                    'a' as i32
-                }
-                'E' => {
-                    end.x = i as i32;
-                    end.y = j as i32;
-                    'z' as i32
-                }
-                val => val as i32,
-            };
-            height_map[i + j * width as usize] = height;
-        }
-    }
-
-    (height_map, start, end)
+fn reachable(from: i32, to: i32) -> bool {
+    to <= from + 1
 }
 
-fn load_all<const N: usize>(raw: &str, width: i32) -> ([i32; N], Vec<Vector2>, Vector2) {
-    let mut height_map = [0i32; N];
-    let mut start = Vec::new();
-    let mut end = Vector2::default();
-
-    for (j, line) in raw.lines().enumerate() {
-        for (i, col) in line.chars().enumerate() {
-            let height = match col {
-                'S' => {
-                    start.push(Vector2 {
-                        x: i as i32,
-                        y: j as i32,
-                    });
-                    'a' as i32
-                }
-                'E' => {
-                    end.x = i as i32;
-                    end.y = j as i32;
-                    'z' as i32
-                }
-                'a' => {
-                    start.push(Vector2 {
-                        x: i as i32,
-                        y: j as i32,
-                    });
-                    'a' as i32
-                }
-                val => val as i32,
-            };
-            height_map[i + j * width as usize] = height;
-        }
-    }
-
-    (height_map, start, end)
+fn shortest_path(height_map: &Grid<i32>, start: Vec2, end: Vec2) -> Option<Path<Vec2, i32>> {
+    search::astar(
+        start,
+        |&from| {
+            height_map
+                .neighbours4(from)
+                .filter(move |&to| reachable(height_map[from], height_map[to]))
+                .map(|to| (to, 1))
+        },
+        |pos| pos.manhattan(end),
+        |&pos| pos == end,
+    )
 }
 
-fn reachable(from: i32, to: i32) -> bool {
-    to <= from + 1
+// Every lowest cell is a possible start, so search backwards from the end instead: the
+// first lowest cell reached is the start with the shortest route. The path comes back
+// the right way round, so it begins at the start that won.
+fn shortest_from_lowest(height_map: &Grid<i32>, end: Vec2) -> Option<Path<Vec2, i32>> {
+    let mut path = search::bfs(
+        end,
+        |&to| {
+            height_map
+                .neighbours4(to)
+                .filter(move |&from| reachable(height_map[from], height_map[to]))
+        },
+        |&pos| height_map[pos] == 'a' as i32,
+    )?;
+
+    path.nodes.reverse();
+    Some(Path {
+        cost: path.cost as i32,
+        nodes: path.nodes,
+    })
 }
 
-fn shortest_path<const N: usize>(
-    height_map: [i32; N],
-    start: Vector2,
-    end: Vector2,
-    width: i32,
-    height: i32,
-) -> Option<i32> {
-    let mut distances = HashMap::new();
-    let mut adjacent = BinaryHeap::new();
-    distances.insert(start, 0);
-    for neighbour in start.neighbours(width, height) {
-        match neighbour {
-            Some(n) => {
-                let n_pos = n.x as usize + width as usize * n.y as usize;
-                let s_pos = start.x as usize + width as usize * start.y as usize;
-                if reachable(height_map[s_pos], height_map[n_pos]) {
-                    adjacent.push(VectorHeuristic {
-                        to: n,
-                        from: start,
-                        distance_to_end: n.distance(end),
-                    });
-                }
-            }
-            _ => (),
-        }
-    }
-    while let Some(consider) = adjacent.pop() {
-        if distances.contains_key(&consider.to) {
-            let new_consider = *distances.get(&consider.from).unwrap() + 1;
-            if distances.get(&consider.to).unwrap() > &new_consider {
-                distances.insert(consider.to, new_consider);
-            } else {
-                continue;
-            }
-        } else {
-            distances.insert(consider.to, distances.get(&consider.from).unwrap() + 1);
-        }
-        // println!(
-        //     "Added {:?}: {} (priority: {})",
-        //     consider.to,
-        //     distances.get(&consider.to).unwrap(),
-        //     consider.distance_to_end,
-        // );
-
-        for neighbour in consider.to.neighbours(width, height) {
-            match neighbour {
-                Some(n) => {
-                    let n_pos = n.x as usize + width as usize * n.y as usize;
-                    let s_pos = consider.to.x as usize + width as usize * consider.to.y as usize;
-                    let next_distance = *distances.get(&consider.to).unwrap() + 1;
-                    if reachable(height_map[s_pos], height_map[n_pos])
-                        && (!distances.contains_key(&n)
-                            || distances.get(&n).unwrap() > &next_distance)
-                    {
-                        adjacent.push(VectorHeuristic {
-                            to: n,
-                            from: consider.to,
-                            distance_to_end: n.distance(end),
-                        });
-                    }
-                }
-                _ => (),
-            }
-        }
+// the route in arrows on an otherwise empty map, like the puzzle's own diagrams. Saved
+// images show the heights around it, from `backdrop`.
+fn draw_route(map: &HeightMap, route: &[Vec2]) -> Grid<char> {
+    let mut picture = Grid::new(map.heights.width(), map.heights.height(), '.');
+    picture[map.start] = 'S';
+    picture[map.end] = 'E';
+
+    for step in route.windows(2) {
+        picture[step[0]] = match step[1] - step[0] {
+            Vec2::UP => '^',
+            Vec2::DOWN => 'v',
+            Vec2::LEFT => '<',
+            _ => '>',
+        };
     }
-    let shortest = *distances.get(&end)?;
-    Some(shortest)
+    picture
 }
 
-fn part1<const N: usize>(raw: &str, width: i32, height: i32) {
-    let (height_map, start, end) = load::<N>(&raw, width);
+fn part1(map: &HeightMap) -> i32 {
+    let shortest = shortest_path(&map.heights, map.start, map.end);
 
-    let shortest = shortest_path::<N>(height_map, start, end, width, height);
+    shortest.expect("no path to the end").cost
+}
+
+fn part2(map: &HeightMap) -> i32 {
+    let shortest = shortest_from_lowest(&map.heights, map.end);
 
-    println!("Part 1: {}", shortest.unwrap());
+    shortest
+        .expect("no path to the end from any lowest point")
+        .cost
 }
 
-fn part2<const N: usize>(raw: &str, width: i32, height: i32) {
-    let (height_map, start, end) = load_all::<N>(&raw, width);
-    let mut path_lengths = Vec::new();
+pub struct Day12;
 
-    for start_vector in start {
-        let shortest = shortest_path::<N>(height_map, start_vector, end, width, height);
-        path_lengths.push(shortest);
+impl Solution for Day12 {
+    type Input = HeightMap;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(raw: &str) -> Result<HeightMap, ParseError> {
+        load(raw)
     }
 
-    println!(
-        "Part 2: {:?}",
-        path_lengths
-            .iter()
-            .map(|a| match a {
-                Some(v) => v,
-                None => &100000,
-            })
-            .fold(100000, |a, b| a.min(*b))
-    );
-}
+    fn part1(map: &HeightMap) -> i32 {
+        part1(map)
+    }
+
+    fn part2(map: &HeightMap) -> i32 {
+        part2(map)
+    }
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day12.example").unwrap(); // 8 x 5
-    part1::<{ 8 * 5 }>(&raw, 8, 5);
-    part2::<{ 8 * 5 }>(&raw, 8, 5);
+    fn draw(map: &HeightMap, part: u8) -> Option<Grid<char>> {
+        let route = match part {
+            1 => shortest_path(&map.heights, map.start, map.end),
+            _ => shortest_from_lowest(&map.heights, map.end),
+        };
+        Some(draw_route(map, &route?.nodes))
+    }
+
+    // the heights under the route, from dark green at `a` up to near white at `z`
+    fn backdrop(map: &HeightMap, _part: u8) -> Option<Grid<Rgb>> {
+        Some(map.heights.map(|&height| {
+            let shade = (height - 'a' as i32) as u8 * 6;
+            [30 + shade, 80 + shade, 30 + shade]
+        }))
+    }
 
-    let raw = fs::read_to_string("data/day12.txt").unwrap(); // 144 x 41
-    part1::<{ 144 * 41 }>(&raw, 144, 41);
-    part2::<{ 144 * 41 }>(&raw, 144, 41);
+    // where the route starts, and how long it is
+    fn explain(map: &HeightMap, part: u8) -> Option<String> {
+        let (route, start) = match part {
+            1 => (shortest_path(&map.heights, map.start, map.end)?, "S"),
+            _ => (
+                shortest_from_lowest(&map.heights, map.end)?,
+                "the closest lowest square",
+            ),
+        };
+        let Vec2 { x, y } = route.nodes[0];
+        Some(format!(
+            "{} is at row {}, column {}, {} steps from E\n",
+            start,
+            y + 1,
+            x + 1,
+            route.cost
+        ))
+    }
 }
@@ -1,4 +1,6 @@
-use std::{cmp::Ordering, fs};
+use std::cmp::Ordering;
+
+use crate::{parser::ParseError, solution::Solution};
 
 fn make_packet(val: &str) -> Vec<(i32, i32)> {
     let mut depth = 0;
@@ -52,11 +54,8 @@ fn make_packet(val: &str) -> Vec<(i32, i32)> {
 // }
 
 fn is_brace(val: i32) -> bool {
-    match val {
-        -1 => true,
-        // -2 => true,
-        _ => false,
-    }
+    // matches!(val, -1 | -2)
+    matches!(val, -1)
 }
 
 fn compare(left: &&str, right: &&str) -> Ordering {
@@ -69,16 +68,16 @@ fn compare(left: &&str, right: &&str) -> Ordering {
     left_stack.reverse();
     right_stack.reverse();
     // println!("Validating {}", index);
-    while is_correct_order == None {
+    while is_correct_order.is_none() {
         let left_val = left_stack.pop();
         let right_val = right_stack.pop();
 
-        if left_val == None && right_val == None {
+        if left_val.is_none() && right_val.is_none() {
             panic!("Ran out of input!");
-        } else if left_val == None {
+        } else if left_val.is_none() {
             is_correct_order = Some(true);
             continue;
-        } else if right_val == None {
+        } else if right_val.is_none() {
             is_correct_order = Some(false);
             continue;
         }
@@ -132,26 +131,24 @@ fn compare(left: &&str, right: &&str) -> Ordering {
     }
 }
 
-fn part1(raw: &str) {
+fn part1(pairs: &[(String, String)]) -> usize {
     let mut sum = 0;
-    for (index, pair_raw) in raw.split("\n\n").enumerate() {
-        let (left, right) = pair_raw.split_once("\n").unwrap();
-        let order = compare(&left, &right);
+    for (index, (left, right)) in pairs.iter().enumerate() {
+        let order = compare(&left.as_str(), &right.as_str());
 
         if order == Ordering::Less {
             sum += index + 1;
         }
     }
 
-    println!("Part 1: {}", sum);
+    sum
 }
 
-fn part2(raw: &str) {
+fn part2(pairs: &[(String, String)]) -> usize {
     let mut packets = Vec::new();
-    for pair_raw in raw.split("\n\n") {
-        let (left, right) = pair_raw.split_once("\n").unwrap();
-        packets.push(left.trim());
-        packets.push(right.trim());
+    for (left, right) in pairs {
+        packets.push(left.as_str());
+        packets.push(right.as_str());
     }
 
     let div_packet1 = "[[2]]";
@@ -168,15 +165,31 @@ fn part2(raw: &str) {
         }
     }
 
-    println!("Part 2: {}", prod);
+    prod
 }
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day13.example").unwrap();
-    part1(&raw);
-    part2(&raw);
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<(String, String)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(raw: &str) -> Result<Self::Input, ParseError> {
+        Ok(raw
+            .split("\n\n")
+            .map(|pair_raw| {
+                let (left, right) = pair_raw.split_once('\n').unwrap();
+                (left.trim().to_string(), right.trim().to_string())
+            })
+            .collect())
+    }
+
+    fn part1(pairs: &Self::Input) -> usize {
+        part1(pairs)
+    }
 
-    let raw = fs::read_to_string("data/day13.txt").unwrap();
-    part1(&raw);
-    part2(&raw);
+    fn part2(pairs: &Self::Input) -> usize {
+        part2(pairs)
+    }
 }
@@ -1,66 +1,42 @@
-use std::{collections::HashMap, fs, marker::PhantomData, ops};
+use crate::{
+    draw::Frames,
+    geometry::{Aabb, Vec2},
+    grid::{Grid, SparseGrid},
+    parser::ParseError,
+    solution::Solution,
+};
 
 const ROCK: char = '#';
 const SAND: char = 'o';
 
-const DOWN: Point = Point { x: 0, y: 1 };
-const DOWN_LEFT: Point = Point { x: -1, y: 1 };
-const DOWN_RIGHT: Point = Point { x: 1, y: 1 };
-const SAND_START: Point = Point { x: 500, y: 0 };
-
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+const SAND_START: Vec2 = Vec2::new(500, 0);
 
 #[derive(Debug)]
 struct LineIterator {
-    start: Point,
-    end: Point,
-    curr: Option<Point>,
-    dir: Point,
-}
-
-impl Point {
-    fn unit_vector(from: Point, to: Point) -> Point {
-        let dir_x = to.x - from.x;
-        let dir_y = to.y - from.y;
-        Point {
-            x: dir_x.signum(),
-            y: dir_y.signum(),
-        }
-    }
-}
-
-impl ops::Add<Self> for Point {
-    type Output = Point;
-    fn add(self, _rhs: Self) -> Point {
-        Point {
-            x: self.x + _rhs.x,
-            y: self.y + _rhs.y,
-        }
-    }
+    start: Vec2,
+    end: Vec2,
+    curr: Option<Vec2>,
+    dir: Vec2,
 }
 
 impl LineIterator {
-    fn new(start: Point, end: Point) -> LineIterator {
+    fn new(start: Vec2, end: Vec2) -> LineIterator {
         LineIterator {
             start,
             end,
             curr: None,
-            dir: Point::unit_vector(start, end),
+            dir: (end - start).signum(),
         }
     }
 }
 
 impl Iterator for LineIterator {
-    type Item = Point;
+    type Item = Vec2;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.curr {
             Some(p) => {
-                if p.x == self.end.x && p.y == self.end.y {
+                if p == self.end {
                     self.curr = None;
                 } else {
                     self.curr = Some(p + self.dir);
@@ -74,92 +50,95 @@ impl Iterator for LineIterator {
     }
 }
 
-fn make_map(raw: &str, with_floor: bool) -> (HashMap<Point, char>, i32) {
-    let mut map = HashMap::new();
+fn make_map(raw: &str) -> (SparseGrid<char>, i32) {
+    let mut map = SparseGrid::new();
     let mut bottom = 0;
     for line in raw.lines() {
         let mut point_string = Vec::new();
         let better_line = line.replace(" -> ", ",");
         let coordinates = better_line
-            .split(",")
-            .collect::<Vec<&str>>()
-            .iter()
+            .split(',')
             .map(|v| v.parse::<i32>().unwrap())
             .collect::<Vec<i32>>();
 
         for i in (0..coordinates.len()).step_by(2) {
-            point_string.push(Point {
-                x: coordinates[i],
-                y: coordinates[i + 1],
-            });
+            point_string.push(Vec2::new(coordinates[i], coordinates[i + 1]));
             bottom = bottom.max(coordinates[i + 1]);
         }
 
         // println!("{:?}", point_string);
         for i in 0..point_string.len() - 1 {
-            let mut iter = LineIterator::new(point_string[i], point_string[i + 1]);
-            while let Some(p) = iter.next() {
+            for p in LineIterator::new(point_string[i], point_string[i + 1]) {
                 // println!("{:?}", p);
                 map.insert(p, ROCK);
             }
         }
     }
 
-    let mut point_string = Vec::new();
-    if with_floor {
-        // println!("Adding floor at y = {:?}", bottom + 2);
-        point_string.push(Point {
-            x: -10000,
-            y: bottom + 2,
-        });
-        point_string.push(Point {
-            x: 10000,
-            y: bottom + 2,
-        });
-        for i in 0..point_string.len() - 1 {
-            let mut iter = LineIterator::new(point_string[i], point_string[i + 1]);
-            while let Some(p) = iter.next() {
-                // println!("{:?}", p);
-                map.insert(p, ROCK);
-            }
-        }
-        bottom += 2
+    (map, bottom)
+}
+
+fn add_floor(map: &mut SparseGrid<char>, bottom: i32) -> i32 {
+    // println!("Adding floor at y = {:?}", bottom + 2);
+    let start = Vec2::new(-10000, bottom + 2);
+    let end = Vec2::new(10000, bottom + 2);
+    for p in LineIterator::new(start, end) {
+        map.insert(p, ROCK);
     }
+    bottom + 2
+}
 
-    (map, bottom)
+fn draw(map: &SparseGrid<char>, bounds: Aabb<2>) -> Grid<char> {
+    map.to_grid(bounds, '.')
 }
 
-fn draw(map: &HashMap<Point, char>, extents: (Point, Point)) {
-    for y in extents.0.y..extents.1.y {
-        for x in extents.0.x..extents.1.x {
-            match map.get(&Point { x, y }) {
-                Some(v) => print!("{}", v),
-                None => print!("."),
-            };
-        }
-        println!();
+// the cave around the rocks and wherever sand can pile up on them. Sand on the floor
+// can spread as far to either side as the floor is deep.
+fn cave_bounds(rocks: &SparseGrid<char>, floor: Option<i32>) -> Aabb<2> {
+    let mut bounds = Aabb::around(SAND_START);
+    for pos in rocks.positions() {
+        bounds.grow(pos);
+    }
+    let mut bounds = bounds.expand(1);
+    if let Some(floor) = floor {
+        bounds.grow(SAND_START + Vec2::new(-floor, floor));
+        bounds.grow(SAND_START + Vec2::new(floor, floor));
     }
+    bounds
 }
 
-fn drop_sand(raw: &str, with_floor: bool) -> i32 {
-    let (mut map, bottom) = make_map(&raw, with_floor);
-    // draw(&map, (Point { x: 492, y: 0 }, Point { x: 492 + 15, y: 15 }));
+// `on_rest` sees the cave and how many units of sand are in it before any sand falls,
+// then again each time a unit comes to rest
+fn drop_sand(
+    rocks: &(SparseGrid<char>, i32),
+    with_floor: bool,
+    on_rest: &mut dyn FnMut(&SparseGrid<char>, usize),
+) -> SparseGrid<char> {
+    let (mut map, mut bottom) = rocks.clone();
+    if with_floor {
+        bottom = add_floor(&mut map, bottom);
+    }
+    // draw(&map, Aabb::new([492, 0], [492 + 14, 14]));
+    let mut units = 0;
+    on_rest(&map, units);
 
     let mut sand = SAND_START;
     while sand.y <= bottom {
-        if !map.contains_key(&(sand + DOWN)) {
+        if !map.contains(sand + Vec2::DOWN) {
             // print!("down ");
-            sand = sand + DOWN;
-        } else if !map.contains_key(&(sand + DOWN_LEFT)) {
+            sand += Vec2::DOWN;
+        } else if !map.contains(sand + Vec2::DOWN_LEFT) {
             // print!("down-left ");
-            sand = sand + DOWN_LEFT;
-        } else if !map.contains_key(&(sand + DOWN_RIGHT)) {
+            sand += Vec2::DOWN_LEFT;
+        } else if !map.contains(sand + Vec2::DOWN_RIGHT) {
             // print!("down-right ");
-            sand = sand + DOWN_RIGHT;
+            sand += Vec2::DOWN_RIGHT;
         } else {
             // comes to rest
             // println!("rest at {:?}", sand);
             map.insert(sand, SAND);
+            units += 1;
+            on_rest(&map, units);
             if sand.y == 0 {
                 break;
             }
@@ -167,25 +146,47 @@ fn drop_sand(raw: &str, with_floor: bool) -> i32 {
         }
     }
     // println!("\n{:?}", sand);
-    // draw(&map, (Point { x: 494, y: 0 }, Point { x: 494 + 10, y: 10 }));
+    // draw(&map, Aabb::new([494, 0], [494 + 9, 9]));
 
-    let sand_count: i32 = map
-        .values()
-        .map(|t| match t {
-            &SAND => 1,
-            _ => 0,
-        })
-        .sum();
+    map
+}
 
-    sand_count
+fn count_sand(map: &SparseGrid<char>) -> i32 {
+    map.values().filter(|&&t| t == SAND).count() as i32
 }
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day14.example").unwrap();
-    println!("Part 1: {}", drop_sand(&raw, false));
-    println!("Part 2: {}", drop_sand(&raw, true));
+pub struct Day14;
 
-    let raw = fs::read_to_string("data/day14.txt").unwrap();
-    println!("Part 1: {}", drop_sand(&raw, false));
-    println!("Part 2: {}", drop_sand(&raw, true));
+impl Solution for Day14 {
+    type Input = (SparseGrid<char>, i32);
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(raw: &str) -> Result<Self::Input, ParseError> {
+        Ok(make_map(raw))
+    }
+
+    fn part1(rocks: &Self::Input) -> i32 {
+        count_sand(&drop_sand(rocks, false, &mut |_map, _units| {}))
+    }
+
+    fn part2(rocks: &Self::Input) -> i32 {
+        count_sand(&drop_sand(rocks, true, &mut |_map, _units| {}))
+    }
+
+    fn draw(rocks: &Self::Input, part: u8) -> Option<Grid<char>> {
+        let with_floor = part == 2;
+        let map = drop_sand(rocks, with_floor, &mut |_map, _units| {});
+        let bounds = cave_bounds(&rocks.0, with_floor.then_some(rocks.1 + 2));
+        Some(draw(&map, bounds))
+    }
+
+    // a frame for each unit of sand
+    fn animate(rocks: &Self::Input, part: u8, frames: &mut Frames) {
+        let with_floor = part == 2;
+        let bounds = cave_bounds(&rocks.0, with_floor.then_some(rocks.1 + 2));
+        drop_sand(rocks, with_floor, &mut |map, units| {
+            frames.step(&[("sand units", units)], || draw(map, bounds))
+        });
+    }
 }
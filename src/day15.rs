@@ -1,4 +1,215 @@
+use std::{collections::HashSet, ops::RangeInclusive, str::FromStr};
 
-pub fn solve() {
-    println!("Solution Pending...");
+use crate::{
+    geometry::Vec2,
+    parser::{self, ParseError, RecordError},
+    solution::Solution,
+};
+
+#[derive(Debug)]
+pub struct Sensor {
+    pos: Vec2,
+    beacon: Vec2,
+    // nothing closer than the beacon can be another beacon
+    radius: i32,
+}
+
+// "x=2, y=18"
+fn point(s: &str) -> Result<Vec2, RecordError> {
+    let bad = || RecordError(format!("expected x=.., y=.., got {:?}", s));
+    let (x, y) = s.split_once(", ").ok_or_else(bad)?;
+    let x = x.strip_prefix("x=").ok_or_else(bad)?.parse()?;
+    let y = y.strip_prefix("y=").ok_or_else(bad)?.parse()?;
+    Ok(Vec2::new(x, y))
+}
+
+impl FromStr for Sensor {
+    type Err = RecordError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (sensor, beacon) = line
+            .strip_prefix("Sensor at ")
+            .and_then(|rest| rest.split_once(": closest beacon is at "))
+            .ok_or_else(|| RecordError(String::from("expected a sensor and its beacon")))?;
+        let (pos, beacon) = (point(sensor)?, point(beacon)?);
+        Ok(Sensor {
+            pos,
+            beacon,
+            radius: pos.manhattan(beacon),
+        })
+    }
+}
+
+impl Sensor {
+    // the cells in `row` that the sensor rules out, if it reaches that far
+    fn coverage(&self, row: i32) -> Option<RangeInclusive<i32>> {
+        let reach = self.radius - (row - self.pos.y).abs();
+        (reach >= 0).then(|| self.pos.x - reach..=self.pos.x + reach)
+    }
+}
+
+// the row to count and the size of the square to search, for inputs that ask about
+// something other than what the real puzzle does
+const QUESTIONS: [(&str, i32, i32); 1] = [("day15.example", 10, 20)];
+const ROW: i32 = 2_000_000;
+const LIMIT: i32 = 4_000_000;
+const REAL_QUESTION: &str =
+    ", going by the real puzzle's question (only day15.example is asked the example's)";
+
+pub struct Report {
+    sensors: Vec<Sensor>,
+    // the row to count in part 1, and the size of the square to search in part 2
+    row: i32,
+    limit: i32,
+}
+
+impl Report {
+    // The row and square only make sense for the input they were picked for. Nothing can
+    // be ruled out in a row no sensor reaches, and the one spot left in the square has
+    // covered cells on every side, so the sensors have to reach every edge of it.
+    fn check(&self) -> Result<(), String> {
+        let reach = |covers: &dyn Fn(&Sensor) -> bool| self.sensors.iter().any(covers);
+        if !reach(&|sensor| sensor.coverage(self.row).is_some()) {
+            return Err(format!("no sensor reaches row {}", self.row));
+        }
+        let edges = reach(&|sensor| sensor.pos.x - sensor.radius <= 0)
+            && reach(&|sensor| sensor.pos.y - sensor.radius <= 0)
+            && reach(&|sensor| sensor.pos.x + sensor.radius >= self.limit)
+            && reach(&|sensor| sensor.pos.y + sensor.radius >= self.limit);
+        if !edges {
+            return Err(format!(
+                "the sensors don't reach every edge of the square from 0 to {}",
+                self.limit
+            ));
+        }
+        Ok(())
+    }
+}
+
+// the cells in `row` that some sensor rules out, as sorted runs that don't touch
+fn coverage(sensors: &[Sensor], row: i32) -> Vec<RangeInclusive<i32>> {
+    let mut ranges: Vec<_> = sensors
+        .iter()
+        .filter_map(|sensor| sensor.coverage(row))
+        .collect();
+    ranges.sort_by_key(|range| *range.start());
+
+    let mut merged: Vec<RangeInclusive<i32>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if *range.start() <= *last.end() + 1 => {
+                *last = *last.start()..=*last.end().max(range.end());
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+fn part1(report: &Report) -> usize {
+    let covered: usize = coverage(&report.sensors, report.row)
+        .iter()
+        .map(|range| range.clone().count())
+        .sum();
+    // the beacons that are there already don't count
+    let beacons: HashSet<Vec2> = report
+        .sensors
+        .iter()
+        .map(|sensor| sensor.beacon)
+        .filter(|beacon| beacon.y == report.row)
+        .collect();
+    covered - beacons.len()
+}
+
+// The cell nobody covers has covered cells on every side, or the edge of the square. So
+// it sits just outside the edges of the sensors' diamonds, where one diagonal edge
+// crosses another, or where one meets the edge of the square. Those are the only rows
+// worth looking at.
+fn candidate_rows(report: &Report) -> Vec<i32> {
+    // x + y and x - y along the diagonals just out of each sensor's reach
+    let mut sums = Vec::new();
+    let mut differences = Vec::new();
+    for sensor in &report.sensors {
+        let (Vec2 { x, y }, reach) = (sensor.pos, sensor.radius + 1);
+        sums.extend([x + y - reach, x + y + reach]);
+        differences.extend([x - y - reach, x - y + reach]);
+    }
+
+    let mut rows = vec![0, report.limit];
+    for &sum in &sums {
+        for &difference in &differences {
+            if (sum - difference) % 2 == 0 {
+                rows.push((sum - difference) / 2);
+            }
+        }
+        rows.extend([sum, sum - report.limit]);
+    }
+    for &difference in &differences {
+        rows.extend([-difference, report.limit - difference]);
+    }
+
+    rows.retain(|row| (0..=report.limit).contains(row));
+    rows.sort();
+    rows.dedup();
+    rows
+}
+
+// the cell in the square that no sensor covers
+fn find_beacon(report: &Report) -> Option<Vec2> {
+    let square = 0..=report.limit;
+    // every row, should the candidates somehow miss it
+    let mut rows = candidate_rows(report).into_iter().chain(square.clone());
+    rows.find_map(|row| {
+        let mut x = 0;
+        for range in coverage(&report.sensors, row) {
+            if *range.start() > x {
+                break;
+            }
+            x = x.max(range.end() + 1);
+        }
+        square.contains(&x).then_some(Vec2::new(x, row))
+    })
+}
+
+fn part2(report: &Report) -> i64 {
+    let beacon = find_beacon(report).expect("the whole square is covered");
+    beacon.x as i64 * 4_000_000 + beacon.y as i64
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Report;
+    type Answer1 = usize;
+    type Answer2 = i64;
+
+    fn parse(raw: &str) -> Result<Report, ParseError> {
+        Ok(Report {
+            sensors: parser::records_from_lines(raw)?,
+            row: ROW,
+            limit: LIMIT,
+        })
+    }
+
+    fn parse_input(input: &str, raw: &str) -> Result<Report, ParseError> {
+        let mut report = Self::parse(raw)?;
+        if let Some(&(_input, row, limit)) = QUESTIONS.iter().find(|question| question.0 == input) {
+            (report.row, report.limit) = (row, limit);
+        }
+        report.check().map_err(|mut err| {
+            if QUESTIONS.iter().all(|question| question.0 != input) {
+                err.push_str(REAL_QUESTION);
+            }
+            ParseError::new(1, raw.lines().next().unwrap_or_default(), RecordError(err))
+        })?;
+        Ok(report)
+    }
+
+    fn part1(report: &Report) -> usize {
+        part1(report)
+    }
+
+    fn part2(report: &Report) -> i64 {
+        part2(report)
+    }
 }
@@ -1,4 +1,308 @@
+use std::{collections::HashMap, fmt::Write as _, str::FromStr};
 
-pub fn solve() {
-    println!("Solution Pending...");
+use crate::{
+    parser::{self, ParseError, RecordError},
+    search,
+    solution::Solution,
+};
+
+const START: &str = "AA";
+
+#[derive(Debug)]
+pub struct Valve {
+    name: String,
+    rate: u32,
+    tunnels: Vec<String>,
+}
+
+impl FromStr for Valve {
+    type Err = RecordError;
+
+    // "Valve BB has flow rate=13; tunnels lead to valves CC, AA", or "tunnel leads to valve"
+    // when there's only one
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let bad = || RecordError(String::from("expected a valve, its flow rate and tunnels"));
+        let rest = line.strip_prefix("Valve ").ok_or_else(bad)?;
+        let (name, rest) = rest.split_once(" has flow rate=").ok_or_else(bad)?;
+        let (rate, tunnels) = rest.split_once(';').ok_or_else(bad)?;
+        let tunnels = ["tunnels lead to valves ", "tunnel leads to valve "]
+            .iter()
+            .find_map(|prefix| tunnels.trim().strip_prefix(prefix))
+            .ok_or_else(bad)?;
+        Ok(Valve {
+            name: String::from(name),
+            rate: rate.parse()?,
+            tunnels: tunnels.split(", ").map(String::from).collect(),
+        })
+    }
+}
+
+// The valves worth opening, and the start, with the minutes it takes to get between each
+// pair of them. The valves that don't release anything are only ever passed through,
+// so they're left out.
+pub struct Network {
+    names: Vec<String>,
+    rates: Vec<u32>,
+    distances: Vec<Vec<u32>>,
+}
+
+// errors come with the index of the valve they're about
+fn compress(valves: &[Valve]) -> Result<Network, (usize, String)> {
+    let index: HashMap<&str, usize> = valves
+        .iter()
+        .enumerate()
+        .map(|(i, valve)| (valve.name.as_str(), i))
+        .collect();
+    let mut tunnels = Vec::new();
+    for (i, valve) in valves.iter().enumerate() {
+        let mut leads_to = Vec::new();
+        for name in &valve.tunnels {
+            match index.get(name.as_str()) {
+                Some(&to) => leads_to.push(to),
+                None => return Err((i, format!("there's no valve {}", name))),
+            }
+        }
+        tunnels.push(leads_to);
+    }
+
+    let start = *index
+        .get(START)
+        .ok_or_else(|| (0, format!("there's no valve {}", START)))?;
+    // the start comes first, whatever its rate
+    let kept: Vec<usize> = std::iter::once(start)
+        .chain((0..valves.len()).filter(|&i| i != start && valves[i].rate > 0))
+        .collect();
+    // sets of valves are bit masks
+    if kept.len() > 64 {
+        return Err((
+            kept[64],
+            String::from("more than 64 valves with a flow rate"),
+        ));
+    }
+
+    let distances = kept
+        .iter()
+        .map(|&from| {
+            kept.iter()
+                .map(|&to| {
+                    search::bfs(from, |&valve| tunnels[valve].clone(), |&valve| valve == to)
+                        .map_or(u32::MAX, |path| path.cost as u32)
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(Network {
+        names: kept.iter().map(|&i| valves[i].name.clone()).collect(),
+        rates: kept.iter().map(|&i| valves[i].rate).collect(),
+        distances,
+    })
+}
+
+// a valve and the minute it's opened at, counting from 1
+#[derive(Debug, Clone, Copy)]
+struct Opening {
+    valve: usize,
+    minute: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Plan {
+    released: u32,
+    openings: Vec<Opening>,
+}
+
+impl Network {
+    // the valves that aren't open yet and can be reached and opened from `at` with time
+    // to spare, with the time that would be left afterwards
+    fn moves(
+        &self,
+        at: usize,
+        time_left: u32,
+        opened: u64,
+    ) -> impl Iterator<Item = (usize, u32)> + '_ {
+        (0..self.names.len())
+            .filter(move |&valve| self.rates[valve] > 0 && opened & (1 << valve) == 0)
+            .filter_map(move |valve| {
+                let minutes = self.distances[at][valve].checked_add(1)?;
+                let time_left = time_left.checked_sub(minutes)?;
+                (time_left > 0).then_some((valve, time_left))
+            })
+    }
+
+    // the most that could be released from here if every closed valve could be reached
+    // straight from `at`, which can't be beaten
+    fn bound(&self, at: usize, time_left: u32, opened: u64, released: u32) -> u32 {
+        released
+            + self
+                .moves(at, time_left, opened)
+                .map(|(valve, left)| self.rates[valve] * left)
+                .sum::<u32>()
+    }
+
+    // the best plan from the start, depth first, giving up on any branch that can't beat
+    // the best found so far
+    fn best_plan(&self, minutes: u32) -> Plan {
+        let mut best = Plan::default();
+        let mut plan = Plan::default();
+        self.search(0, minutes, minutes, 0, &mut plan, &mut best);
+        best
+    }
+
+    fn search(
+        &self,
+        at: usize,
+        minutes: u32,
+        time_left: u32,
+        opened: u64,
+        plan: &mut Plan,
+        best: &mut Plan,
+    ) {
+        if plan.released > best.released {
+            *best = plan.clone();
+        }
+        if self.bound(at, time_left, opened, plan.released) <= best.released {
+            return;
+        }
+        for (valve, left) in self.moves(at, time_left, opened) {
+            plan.released += self.rates[valve] * left;
+            plan.openings.push(Opening {
+                valve,
+                minute: minutes - left,
+            });
+            self.search(valve, minutes, left, opened | 1 << valve, plan, best);
+            plan.openings.pop();
+            plan.released -= self.rates[valve] * left;
+        }
+    }
+
+    // The best plan for every set of valves that can be opened, by their bit mask. Two
+    // people working at once can't both open a valve, so the best they can do between
+    // them is the best pair of plans for sets that don't overlap.
+    fn plans_by_valves(&self, minutes: u32) -> HashMap<u64, Plan> {
+        let mut plans = HashMap::new();
+        let mut plan = Plan::default();
+        self.every_plan(0, minutes, minutes, 0, &mut plan, &mut plans);
+        plans
+    }
+
+    fn every_plan(
+        &self,
+        at: usize,
+        minutes: u32,
+        time_left: u32,
+        opened: u64,
+        plan: &mut Plan,
+        plans: &mut HashMap<u64, Plan>,
+    ) {
+        let best = plans.entry(opened).or_default();
+        if plan.released > best.released {
+            *best = plan.clone();
+        }
+        for (valve, left) in self.moves(at, time_left, opened) {
+            plan.released += self.rates[valve] * left;
+            plan.openings.push(Opening {
+                valve,
+                minute: minutes - left,
+            });
+            self.every_plan(valve, minutes, left, opened | 1 << valve, plan, plans);
+            plan.openings.pop();
+            plan.released -= self.rates[valve] * left;
+        }
+    }
+
+    // you and the elephant, each with a plan for valves the other leaves alone
+    fn best_pair(&self, minutes: u32) -> (Plan, Plan) {
+        let mut plans: Vec<(u64, Plan)> = self.plans_by_valves(minutes).into_iter().collect();
+        plans.sort_by_key(|(_valves, plan)| std::cmp::Reverse(plan.released));
+
+        let mut best = (0, 0);
+        let mut best_released = 0;
+        for (i, (mine, my_plan)) in plans.iter().enumerate() {
+            // the rest are sorted, so nothing further on can make up the difference
+            if my_plan.released * 2 <= best_released {
+                break;
+            }
+            for (j, (theirs, their_plan)) in plans.iter().enumerate().skip(i) {
+                let released = my_plan.released + their_plan.released;
+                if released <= best_released {
+                    break;
+                }
+                if mine & theirs == 0 {
+                    best = (i, j);
+                    best_released = released;
+                }
+            }
+        }
+        (plans[best.0].1.clone(), plans[best.1].1.clone())
+    }
+
+    fn describe(&self, who_opens: &str, plan: &Plan, minutes: u32, out: &mut String) {
+        for opening in &plan.openings {
+            let rate = self.rates[opening.valve];
+            writeln!(
+                out,
+                "{} {} at minute {}, releasing {} x {} = {}",
+                who_opens,
+                self.names[opening.valve],
+                opening.minute,
+                rate,
+                minutes - opening.minute,
+                rate * (minutes - opening.minute)
+            )
+            .unwrap();
+        }
+    }
+}
+
+fn part1(network: &Network) -> u32 {
+    network.best_plan(30).released
+}
+
+fn part2(network: &Network) -> u32 {
+    let (mine, theirs) = network.best_pair(26);
+    mine.released + theirs.released
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Network;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(raw: &str) -> Result<Network, ParseError> {
+        let valves: Vec<Valve> = parser::records_from_lines(raw)?;
+        compress(&valves).map_err(|(i, err)| {
+            ParseError::new(
+                i + 1,
+                raw.lines().nth(i).unwrap_or_default(),
+                RecordError(err),
+            )
+        })
+    }
+
+    fn part1(network: &Network) -> u32 {
+        part1(network)
+    }
+
+    fn part2(network: &Network) -> u32 {
+        part2(network)
+    }
+
+    // the order the valves are opened in, and what each one releases
+    fn explain(network: &Network, part: u8) -> Option<String> {
+        let mut out = String::new();
+        if part == 1 {
+            let plan = network.best_plan(30);
+            network.describe("you open", &plan, 30, &mut out);
+            writeln!(out, "{} released", plan.released).unwrap();
+        } else {
+            let (mine, theirs) = network.best_pair(26);
+            network.describe("you open", &mine, 26, &mut out);
+            network.describe("the elephant opens", &theirs, 26, &mut out);
+            writeln!(out, "{} released", mine.released + theirs.released).unwrap();
+        }
+        Some(out)
+    }
 }
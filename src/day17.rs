@@ -1,4 +1,201 @@
+use std::collections::HashMap;
 
-pub fn solve() {
-    println!("Solution Pending...");
+use crate::{
+    grid::Grid,
+    parser::{ParseError, RecordError},
+    solution::Solution,
+};
+
+const WIDTH: usize = 7;
+
+// Rows of the chamber are bit masks, with the leftmost column in bit 6. Rocks are the
+// same, from the bottom up, and start two cells in from the left wall.
+const ROCKS: [&[u8]; 5] = [
+    &[0b0011110],
+    &[0b0001000, 0b0011100, 0b0001000],
+    &[0b0011100, 0b0000100, 0b0000100],
+    &[0b0010000, 0b0010000, 0b0010000, 0b0010000],
+    &[0b0011000, 0b0011000],
+];
+
+// how far down the skyline looks for cycles. Anything deeper is taken to be covered up.
+const SKYLINE_DEPTH: u8 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jet {
+    Left,
+    Right,
+}
+
+struct Tower<'a> {
+    jets: &'a [Jet],
+    rows: Vec<u8>,
+    rocks: usize,
+    jet: usize,
+}
+
+impl<'a> Tower<'a> {
+    fn new(jets: &'a [Jet]) -> Tower<'a> {
+        Tower {
+            jets,
+            rows: Vec::new(),
+            rocks: 0,
+            jet: 0,
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn fits(&self, rock: &[u8], bottom: usize) -> bool {
+        rock.iter().enumerate().all(|(i, row)| {
+            self.rows
+                .get(bottom + i)
+                .is_none_or(|filled| filled & row == 0)
+        })
+    }
+
+    fn drop_rock(&mut self) {
+        let mut rock = ROCKS[self.rocks % ROCKS.len()].to_vec();
+        let mut bottom = self.height() + 3;
+        loop {
+            let pushed: Option<Vec<u8>> = match self.jets[self.jet] {
+                Jet::Left => rock
+                    .iter()
+                    .map(|row| (row & 1 << (WIDTH - 1) == 0).then_some(row << 1))
+                    .collect(),
+                Jet::Right => rock
+                    .iter()
+                    .map(|row| (row & 1 == 0).then_some(row >> 1))
+                    .collect(),
+            };
+            self.jet = (self.jet + 1) % self.jets.len();
+            if let Some(pushed) = pushed.filter(|pushed| self.fits(pushed, bottom)) {
+                rock = pushed;
+            }
+
+            if bottom == 0 || !self.fits(&rock, bottom - 1) {
+                break;
+            }
+            bottom -= 1;
+        }
+
+        for (i, row) in rock.iter().enumerate() {
+            match self.rows.get_mut(bottom + i) {
+                Some(filled) => *filled |= row,
+                None => self.rows.push(*row),
+            }
+        }
+        self.rocks += 1;
+    }
+
+    // how far below the top each column's highest rock is
+    fn skyline(&self) -> [u8; WIDTH] {
+        let mut skyline = [SKYLINE_DEPTH; WIDTH];
+        for (column, depth) in skyline.iter_mut().enumerate() {
+            let bit = 1 << (WIDTH - 1 - column);
+            if let Some(found) = self.rows.iter().rev().position(|row| row & bit != 0) {
+                *depth = (found as u8).min(SKYLINE_DEPTH);
+            }
+        }
+        skyline
+    }
+
+    // the top `rows` rows of the tower as the puzzle draws them
+    fn draw(&self, rows: usize) -> Grid<char> {
+        let bottom = self.height().saturating_sub(rows);
+        let mut lines: Vec<Vec<char>> = self.rows[bottom..]
+            .iter()
+            .rev()
+            .map(|row| {
+                let cells = (0..WIDTH).map(|x| match row & 1 << (WIDTH - 1 - x) {
+                    0 => '.',
+                    _ => '#',
+                });
+                std::iter::once('|').chain(cells).chain(['|']).collect()
+            })
+            .collect();
+        if bottom == 0 {
+            lines.push(format!("+{}+", "-".repeat(WIDTH)).chars().collect());
+        }
+        Grid::from_rows(lines).expect("rows are all the same width")
+    }
+}
+
+// The tower after `rocks` have fallen, and how much taller it really is than the part that
+// was simulated. Once the next rock, the next jet and the shape of the top all repeat, so
+// does everything after, so whole cycles are skipped over and only what's left over is
+// dropped. The top of the tower then looks just as it would have.
+fn build(jets: &[Jet], rocks: usize) -> (Tower<'_>, usize) {
+    let mut tower = Tower::new(jets);
+    let mut seen = HashMap::new();
+
+    while tower.rocks < rocks {
+        let state = (tower.rocks % ROCKS.len(), tower.jet, tower.skyline());
+        if let Some((rocks_then, height_then)) = seen.insert(state, (tower.rocks, tower.height())) {
+            let period = tower.rocks - rocks_then;
+            let cycles = (rocks - tower.rocks) / period;
+            let skipped = cycles * (tower.height() - height_then);
+            for _ in 0..(rocks - tower.rocks) % period {
+                tower.drop_rock();
+            }
+            return (tower, skipped);
+        }
+        tower.drop_rock();
+    }
+    (tower, 0)
+}
+
+fn height_after(jets: &[Jet], rocks: usize) -> usize {
+    let (tower, skipped) = build(jets, rocks);
+    tower.height() + skipped
+}
+
+fn parse(raw: &str) -> Result<Vec<Jet>, ParseError> {
+    let line = raw.trim();
+    let jets = line
+        .chars()
+        .map(|c| match c {
+            '<' => Ok(Jet::Left),
+            '>' => Ok(Jet::Right),
+            c => Err(RecordError(format!("{:?} isn't a jet", c))),
+        })
+        .collect::<Result<Vec<Jet>, RecordError>>()
+        .map_err(|err| ParseError::new(1, line, err))?;
+    if jets.is_empty() {
+        return Err(ParseError::new(
+            1,
+            line,
+            RecordError(String::from("no jets")),
+        ));
+    }
+    Ok(jets)
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Vec<Jet>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(raw: &str) -> Result<Vec<Jet>, ParseError> {
+        parse(raw)
+    }
+
+    fn part1(jets: &Vec<Jet>) -> usize {
+        height_after(jets, 2022)
+    }
+
+    fn part2(jets: &Vec<Jet>) -> usize {
+        height_after(jets, 1_000_000_000_000)
+    }
+
+    // the top of the tower, which is the same whether it was all simulated or not
+    fn draw(jets: &Vec<Jet>, part: u8) -> Option<Grid<char>> {
+        let rocks = if part == 1 { 2022 } else { 1_000_000_000_000 };
+        let (tower, _skipped) = build(jets, rocks);
+        Some(tower.draw(30))
+    }
 }
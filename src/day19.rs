@@ -1,4 +1,245 @@
+use std::{fmt::Write as _, str::FromStr};
 
-pub fn solve() {
-    println!("Solution Pending...");
+use crate::{
+    parser::{self, ParseError, RecordError},
+    solution::Solution,
+};
+
+const ORE: usize = 0;
+const GEODE: usize = 3;
+const RESOURCES: [&str; 4] = ["ore", "clay", "obsidian", "geode"];
+
+// an amount of each resource, indexed like `RESOURCES`
+type Stock = [u32; 4];
+
+#[derive(Debug)]
+pub struct Blueprint {
+    id: u32,
+    // what a robot collecting each resource costs
+    costs: [Stock; 4],
+}
+
+fn resource(name: &str) -> Result<usize, RecordError> {
+    RESOURCES
+        .iter()
+        .position(|resource| *resource == name)
+        .ok_or_else(|| RecordError(format!("unknown resource {:?}", name)))
+}
+
+impl FromStr for Blueprint {
+    type Err = RecordError;
+
+    // "Blueprint 1: Each ore robot costs 4 ore. Each obsidian robot costs 3 ore and 14 clay. ..."
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (id, robots) = line
+            .strip_prefix("Blueprint ")
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or_else(|| RecordError(String::from("expected a blueprint number")))?;
+
+        let mut costs = [None; 4];
+        for sentence in robots.split('.').map(str::trim).filter(|s| !s.is_empty()) {
+            let bad = || RecordError(format!("expected a robot and its cost, got {:?}", sentence));
+            let (robot, cost) = sentence
+                .strip_prefix("Each ")
+                .and_then(|rest| rest.split_once(" robot costs "))
+                .ok_or_else(bad)?;
+            let mut stock = [0; 4];
+            for amount in cost.split(" and ") {
+                stock[resource(parser::field(amount, 1, "resource")?)?] =
+                    parser::field(amount, 0, "amount")?.parse()?;
+            }
+            costs[resource(robot)?] = Some(stock);
+        }
+
+        let mut blueprint = Blueprint {
+            id: id.parse()?,
+            costs: [[0; 4]; 4],
+        };
+        for (robot, cost) in costs.into_iter().enumerate() {
+            blueprint.costs[robot] = cost.ok_or_else(|| {
+                RecordError(format!("no cost for the {} robot", RESOURCES[robot]))
+            })?;
+        }
+        Ok(blueprint)
+    }
+}
+
+// a robot and the minute it's built in, counting from 1
+#[derive(Debug, Clone, Copy)]
+struct Build {
+    minute: u32,
+    robot: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Plan {
+    geodes: u32,
+    builds: Vec<Build>,
+}
+
+struct Search<'a> {
+    blueprint: &'a Blueprint,
+    minutes: u32,
+    // There's no use having more robots collecting a resource than the most that can be
+    // spent in a minute, as only one robot can be built at a time. Geodes aren't spent.
+    caps: Stock,
+    plan: Plan,
+    best: Plan,
+}
+
+impl Search<'_> {
+    // Depth first, choosing which robot to save up for next rather than what to do each
+    // minute, and giving up on any branch that couldn't beat the best plan so far even if
+    // it built a geode robot every minute from here on.
+    fn search(&mut self, time_left: u32, robots: Stock, stock: Stock) {
+        let geodes = stock[GEODE] + robots[GEODE] * time_left;
+        if geodes > self.best.geodes {
+            self.best = Plan {
+                geodes,
+                builds: self.plan.builds.clone(),
+            };
+        }
+        let bound = geodes + time_left * time_left.saturating_sub(1) / 2;
+        if bound <= self.best.geodes {
+            return;
+        }
+
+        // geode robots first, which finds good plans early and prunes more
+        for robot in (0..RESOURCES.len()).rev() {
+            if robots[robot] >= self.caps[robot] {
+                continue;
+            }
+            let Some(wait) = self.wait(robot, &robots, &stock) else {
+                continue;
+            };
+            // a robot finished in the last minute doesn't collect anything
+            if wait + 1 >= time_left {
+                continue;
+            }
+
+            let cost = &self.blueprint.costs[robot];
+            let mut next_stock = stock;
+            for resource in 0..RESOURCES.len() {
+                next_stock[resource] += robots[resource] * (wait + 1);
+                next_stock[resource] -= cost[resource];
+            }
+            let mut next_robots = robots;
+            next_robots[robot] += 1;
+
+            self.plan.builds.push(Build {
+                minute: self.minutes - time_left + wait + 1,
+                robot,
+            });
+            self.search(time_left - wait - 1, next_robots, next_stock);
+            self.plan.builds.pop();
+        }
+    }
+
+    // the minutes to wait before `robot` can be afforded, or none if nothing's collecting
+    // something it needs
+    fn wait(&self, robot: usize, robots: &Stock, stock: &Stock) -> Option<u32> {
+        let cost = &self.blueprint.costs[robot];
+        (0..RESOURCES.len())
+            .map(|resource| {
+                let needed = cost[resource].saturating_sub(stock[resource]);
+                match (needed, robots[resource]) {
+                    (0, _) => Some(0),
+                    (_, 0) => None,
+                    (needed, collecting) => Some(needed.div_ceil(collecting)),
+                }
+            })
+            .try_fold(0, |most, wait| Some(most.max(wait?)))
+    }
+}
+
+fn best_plan(blueprint: &Blueprint, minutes: u32) -> Plan {
+    let mut caps = [u32::MAX; 4];
+    for (resource, cap) in caps.iter_mut().enumerate().take(GEODE) {
+        *cap = blueprint
+            .costs
+            .iter()
+            .map(|cost| cost[resource])
+            .max()
+            .unwrap();
+    }
+    let mut search = Search {
+        blueprint,
+        minutes,
+        caps,
+        plan: Plan::default(),
+        best: Plan::default(),
+    };
+    let mut robots = [0; 4];
+    robots[ORE] = 1;
+    search.search(minutes, robots, [0; 4]);
+    search.best
+}
+
+fn quality_level(blueprint: &Blueprint) -> u32 {
+    blueprint.id * best_plan(blueprint, 24).geodes
+}
+
+fn part1(blueprints: &[Blueprint]) -> u32 {
+    blueprints.iter().map(quality_level).sum()
+}
+
+fn part2(blueprints: &[Blueprint]) -> u32 {
+    blueprints
+        .iter()
+        .take(3)
+        .map(|blueprint| best_plan(blueprint, 32).geodes)
+        .product()
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Vec<Blueprint>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(raw: &str) -> Result<Vec<Blueprint>, ParseError> {
+        parser::records_from_lines(raw)
+    }
+
+    fn part1(blueprints: &Vec<Blueprint>) -> u32 {
+        part1(blueprints)
+    }
+
+    fn part2(blueprints: &Vec<Blueprint>) -> u32 {
+        part2(blueprints)
+    }
+
+    // the geodes each blueprint opens, and the robots it builds to do it
+    fn explain(blueprints: &Vec<Blueprint>, part: u8) -> Option<String> {
+        let (minutes, count) = if part == 1 {
+            (24, blueprints.len())
+        } else {
+            (32, 3)
+        };
+        let mut out = String::new();
+        for blueprint in blueprints.iter().take(count) {
+            let plan = best_plan(blueprint, minutes);
+            write!(out, "blueprint {}: {} geodes", blueprint.id, plan.geodes).unwrap();
+            if part == 1 {
+                write!(out, ", quality level {}", blueprint.id * plan.geodes).unwrap();
+            }
+            writeln!(out).unwrap();
+            for build in &plan.builds {
+                let robot = RESOURCES[build.robot];
+                let article = if robot.starts_with(['a', 'e', 'i', 'o', 'u']) {
+                    "an"
+                } else {
+                    "a"
+                };
+                writeln!(
+                    out,
+                    "  minute {}: build {} {} robot",
+                    build.minute, article, robot
+                )
+                .unwrap();
+            }
+        }
+        Some(out)
+    }
 }
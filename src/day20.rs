@@ -1,6 +1,8 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fs,
+use std::collections::VecDeque;
+
+use crate::{
+    parser::{self, ParseError},
+    solution::Solution,
 };
 
 fn rotate_any(working: &mut VecDeque<(usize, i64)>, val: i64) {
@@ -35,12 +37,11 @@ fn mix(working: &mut VecDeque<(usize, i64)>) {
     working.push_back((mix_index, mix_val));
 }
 
-fn part1(raw: &str) {
+fn part1(values: &[i64]) -> i64 {
     let mut encoded = VecDeque::new();
 
-    for (index, line) in raw.lines().enumerate() {
-        let val: i64 = line.parse().unwrap();
-        encoded.push_back((index, val));
+    for (index, val) in values.iter().enumerate() {
+        encoded.push_back((index, *val));
     }
 
     let mut decode = encoded.clone();
@@ -52,20 +53,14 @@ fn part1(raw: &str) {
         // println!("Rotate {}: {:?}", v, decode);
     }
     rotate_any(&mut decode, 0);
-    println!(
-        "Part 1: {}",
-        decode[1000 % decode.len()].1
-            + decode[2000 % decode.len()].1
-            + decode[3000 % decode.len()].1
-    );
+    decode[1000 % decode.len()].1 + decode[2000 % decode.len()].1 + decode[3000 % decode.len()].1
 }
 
-fn part2(raw: &str) {
+fn part2(values: &[i64]) -> i64 {
     let key = 811589153;
     let mut encoded = VecDeque::new();
 
-    for (index, line) in raw.lines().enumerate() {
-        let val: i64 = line.parse().unwrap();
+    for (index, val) in values.iter().enumerate() {
         encoded.push_back((index, val * key));
     }
 
@@ -85,20 +80,25 @@ fn part2(raw: &str) {
         }
     }
     rotate_any(&mut decode, 0);
-    println!(
-        "Part 2: {}",
-        decode[1000 % decode.len()].1
-            + decode[2000 % decode.len()].1
-            + decode[3000 % decode.len()].1
-    );
+    decode[1000 % decode.len()].1 + decode[2000 % decode.len()].1 + decode[3000 % decode.len()].1
 }
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day20.example").unwrap();
-    part1(&raw);
-    part2(&raw);
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Vec<i64>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(raw: &str) -> Result<Vec<i64>, ParseError> {
+        parser::records_from_lines(raw)
+    }
 
-    let raw = fs::read_to_string("data/day20.txt").unwrap();
-    part1(&raw);
-    part2(&raw);
+    fn part1(values: &Vec<i64>) -> i64 {
+        part1(values)
+    }
+
+    fn part2(values: &Vec<i64>) -> i64 {
+        part2(values)
+    }
 }
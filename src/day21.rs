@@ -1,4 +1,6 @@
-use std::{collections::HashMap, fs};
+use std::collections::HashMap;
+
+use crate::{parser::ParseError, solution::Solution};
 
 #[derive(Debug, Clone, Copy)]
 enum Op {
@@ -39,7 +41,7 @@ impl Op {
 }
 
 #[derive(Debug, Clone)]
-struct ValOrOp {
+pub struct ValOrOp {
     value: Option<i64>,
     operation: Option<Op>,
     variable: Option<String>,
@@ -71,11 +73,10 @@ impl ValOrOp {
     }
 }
 
-fn calculate1(part: i32, resolve: &Vec<ValOrOp>) {
+fn calculate1(resolve: &[ValOrOp]) -> i64 {
     let mut stack = Vec::new();
     for step in resolve {
-        if step.value != None {
-            let value = step.value.unwrap();
+        if let Some(value) = step.value {
             stack.push(value);
             //println!("Pushed {}", value);
         } else {
@@ -87,16 +88,14 @@ fn calculate1(part: i32, resolve: &Vec<ValOrOp>) {
             stack.push(result);
         }
     }
-    let final_result = stack.pop().unwrap();
-    println!("Part {}: {}", part, final_result);
+    stack.pop().unwrap()
 }
 
-fn calculate2(part: i32, resolve: &Vec<ValOrOp>) {
+fn calculate2(resolve: &[ValOrOp]) -> i64 {
     let mut stack = Vec::new();
     let mut alt_stack = Vec::new();
     for step in resolve {
-        if step.value != None {
-            let value = step.value.unwrap();
+        if let Some(value) = step.value {
             stack.push(value);
             //println!("Pushed {}", value);
         } else {
@@ -124,18 +123,19 @@ fn calculate2(part: i32, resolve: &Vec<ValOrOp>) {
 
     alt_stack.push(ValOrOp::as_val(0));
     alt_stack.reverse();
-    calculate1(part, &alt_stack);
+    calculate1(&alt_stack)
 }
 
-fn make_monkeys(raw: &str) -> HashMap<&str, Vec<ValOrOp>> {
+fn make_monkeys(raw: &str) -> HashMap<String, Vec<ValOrOp>> {
     let mut monkeys = HashMap::new();
     for line in raw.lines() {
-        let (monkey, operation) = line.split_once(":").unwrap();
+        let (monkey, operation) = line.split_once(':').unwrap();
+        let monkey = String::from(monkey);
         let operation = operation.trim();
         match operation.parse::<i64>() {
             Ok(i) => monkeys.insert(monkey, vec![ValOrOp::as_val(i)]),
-            Err(err) => {
-                let parts: Vec<&str> = operation.split(" ").collect();
+            Err(_) => {
+                let parts: Vec<&str> = operation.split(' ').collect();
                 monkeys.insert(
                     monkey,
                     vec![
@@ -150,21 +150,19 @@ fn make_monkeys(raw: &str) -> HashMap<&str, Vec<ValOrOp>> {
     monkeys
 }
 
-fn postfixify(monkeys: &mut HashMap<&str, Vec<ValOrOp>>, keep_human: bool) -> Vec<ValOrOp> {
+fn postfixify(monkeys: &mut HashMap<String, Vec<ValOrOp>>, keep_human: bool) -> Vec<ValOrOp> {
     let mut resolve = monkeys.remove("root").unwrap();
     let mut index = 0;
     while index < resolve.len() {
         let current = resolve[index].clone();
-        if current.variable != None {
-            let find = current.variable.unwrap().clone();
+        if let Some(find) = current.variable {
             if keep_human && find == "humn" {
                 // 0 is not in the set, maybe we calculate to it though...
                 resolve.splice(index..index + 1, vec![ValOrOp::as_val(0)]);
                 index += 1;
                 continue;
             }
-            let find = find.as_str();
-            let insert = monkeys.remove(find).unwrap();
+            let insert = monkeys.remove(&find).unwrap();
             resolve.splice(index..index + 1, insert);
         } else {
             index += 1
@@ -173,29 +171,40 @@ fn postfixify(monkeys: &mut HashMap<&str, Vec<ValOrOp>>, keep_human: bool) -> Ve
     resolve
 }
 
-fn part1(raw: &str) {
-    let mut monkeys = make_monkeys(raw);
+fn part1(monkeys: &HashMap<String, Vec<ValOrOp>>) -> i64 {
+    let mut monkeys = monkeys.clone();
     let resolve = postfixify(&mut monkeys, false);
-    calculate1(1, &resolve);
+    calculate1(&resolve)
 }
 
-fn part2(raw: &str) {
-    let mut monkeys = make_monkeys(raw);
+fn part2(monkeys: &HashMap<String, Vec<ValOrOp>>) -> i64 {
+    let mut monkeys = monkeys.clone();
     let root = monkeys.remove("root").unwrap();
     monkeys.insert(
-        "root",
+        String::from("root"),
         vec![root[0].clone(), root[1].clone(), ValOrOp::as_op(Op::Sub)],
     );
     let resolve = postfixify(&mut monkeys, true);
-    calculate2(2, &resolve);
+    calculate2(&resolve)
 }
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day21.example").unwrap();
-    part1(&raw);
-    part2(&raw); // works (301)
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = HashMap<String, Vec<ValOrOp>>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(raw: &str) -> Result<Self::Input, ParseError> {
+        Ok(make_monkeys(raw))
+    }
 
-    let raw = fs::read_to_string("data/day21.txt").unwrap();
-    part1(&raw);
-    part2(&raw); // incorrect (9626251584895)
+    fn part1(monkeys: &Self::Input) -> i64 {
+        part1(monkeys)
+    }
+
+    // works on the example (301), incorrect on the real input (9626251584895)
+    fn part2(monkeys: &Self::Input) -> i64 {
+        part2(monkeys)
+    }
 }
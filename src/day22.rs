@@ -1,4 +1,319 @@
+use std::collections::HashMap;
 
-pub fn solve() {
-    println!("Solution Pending...");
+use crate::{
+    geometry::{Vec2, Vec3},
+    grid::Grid,
+    parser::{self, ParseError, RecordError},
+    solution::Solution,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    // off the edge of the map
+    Void,
+    Open,
+    Wall,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Forward(u32),
+    Left,
+    Right,
+}
+
+pub struct Notes {
+    board: Grid<Tile>,
+    path: Vec<Step>,
+    // only part 2 needs the board to fold up, so part 1 can still walk one that doesn't
+    cube: Result<Cube, String>,
+}
+
+fn on_board(board: &Grid<Tile>, pos: Vec2) -> bool {
+    board.get(pos).is_some_and(|&tile| tile != Tile::Void)
+}
+
+/// Where you end up, and which way you're facing, after walking off the edge of the board
+/// from `pos` facing `facing`.
+trait Wrapping {
+    fn wrap(&self, pos: Vec2, facing: Vec2) -> (Vec2, Vec2);
+}
+
+// the board as a torus: off one edge and back on at the far side of the same row or column
+struct Flat<'a> {
+    board: &'a Grid<Tile>,
+}
+
+impl Wrapping for Flat<'_> {
+    fn wrap(&self, pos: Vec2, facing: Vec2) -> (Vec2, Vec2) {
+        let mut pos = pos;
+        while on_board(self.board, pos - facing) {
+            pos -= facing;
+        }
+        (pos, facing)
+    }
+}
+
+// where a face of the net ends up once it's folded, as the outward normal of the cube face
+// and the directions its right and down point in
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    corner: Vec2,
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Face {
+    fn direction(&self, facing: Vec2) -> Vec3 {
+        self.right * facing.x + self.down * facing.y
+    }
+
+    fn facing(&self, direction: Vec3) -> Vec2 {
+        Vec2::new(direction.dot(self.right), direction.dot(self.down))
+    }
+}
+
+// The board folded up into a cube. The centre of every tile gets a point on the surface,
+// with coordinates doubled so they're all whole, on a cube from -size to size. Walking off
+// an edge turns the way you're facing into the normal of the next face round, and you land
+// on the tile half a step further that way and half a step in from the face you left.
+struct Cube {
+    size: i32,
+    faces: HashMap<Vec2, Face>,
+    tiles: HashMap<Vec3, Vec2>,
+}
+
+impl Cube {
+    // Fold the net, whatever shape it is. Starting from any face, each face next to one
+    // that's been placed is folded down over the edge between them, which turns it by a
+    // right angle about that edge.
+    fn fold(board: &Grid<Tile>) -> Result<Cube, String> {
+        let tiles = board
+            .iter()
+            .filter(|(_pos, &tile)| tile != Tile::Void)
+            .count();
+        let size = (1..)
+            .map(|size| size * size)
+            .take_while(|&area| area * 6 <= tiles)
+            .count();
+        if size == 0 || size * size * 6 != tiles {
+            return Err(format!(
+                "{} tiles can't cover the six faces of a cube",
+                tiles
+            ));
+        }
+        let size = size as i32;
+
+        let blocks: Vec<Vec2> = board
+            .positions()
+            .filter(|pos| pos.x % size == 0 && pos.y % size == 0 && on_board(board, *pos))
+            .map(|pos| pos / size)
+            .collect();
+        let Some(&first) = blocks.first() else {
+            return Err(String::from("the board is empty"));
+        };
+
+        let mut faces = HashMap::new();
+        faces.insert(
+            first,
+            Face {
+                corner: first * size,
+                normal: Vec3::new(0, 0, -1),
+                right: Vec3::X,
+                down: Vec3::Y,
+            },
+        );
+        let mut queue = vec![first];
+        while let Some(block) = queue.pop() {
+            let face = faces[&block];
+            for step in Vec2::ORTHOGONAL {
+                let next = block + step;
+                if faces.contains_key(&next) || !blocks.contains(&next) {
+                    continue;
+                }
+                let over = face.direction(step);
+                let mut folded = Face {
+                    corner: next * size,
+                    normal: over,
+                    ..face
+                };
+                if step.x != 0 {
+                    folded.right = face.normal * -step.x;
+                } else {
+                    folded.down = face.normal * -step.y;
+                }
+                faces.insert(next, folded);
+                queue.push(next);
+            }
+        }
+
+        let mut normals: Vec<Vec3> = faces.values().map(|face| face.normal).collect();
+        normals.sort();
+        normals.dedup();
+        // every tile has to be on one of the faces, or they don't line up into squares
+        let unfolded = board
+            .iter()
+            .any(|(pos, &tile)| tile != Tile::Void && !faces.contains_key(&(pos / size)));
+        if faces.len() != blocks.len() || normals.len() != 6 || unfolded {
+            return Err(String::from("the board doesn't fold up into a cube"));
+        }
+
+        let mut cube = Cube {
+            size,
+            faces,
+            tiles: HashMap::new(),
+        };
+        for (pos, &tile) in board.iter() {
+            if tile != Tile::Void {
+                cube.tiles.insert(cube.point(pos), pos);
+            }
+        }
+        Ok(cube)
+    }
+
+    fn face(&self, pos: Vec2) -> &Face {
+        &self.faces[&(pos / self.size)]
+    }
+
+    fn point(&self, pos: Vec2) -> Vec3 {
+        let face = self.face(pos);
+        let across = (pos - face.corner) * 2 - Vec2::new(self.size - 1, self.size - 1);
+        face.normal * self.size + face.direction(across)
+    }
+}
+
+impl Wrapping for Cube {
+    fn wrap(&self, pos: Vec2, facing: Vec2) -> (Vec2, Vec2) {
+        let face = self.face(pos);
+        let over = face.direction(facing);
+        let landed = self.tiles[&(self.point(pos) + over - face.normal)];
+        // heading away from the face just left
+        (landed, self.face(landed).facing(-face.normal))
+    }
+}
+
+// the password for where the path ends up
+fn walk(notes: &Notes, wrapping: &dyn Wrapping) -> i32 {
+    let start = notes
+        .board
+        .position(|&tile| tile == Tile::Open)
+        .expect("there's nowhere to start");
+    let (mut pos, mut facing) = (start, Vec2::RIGHT);
+
+    for &step in &notes.path {
+        match step {
+            Step::Left => facing = facing.rotate_left(),
+            Step::Right => facing = facing.rotate_right(),
+            Step::Forward(tiles) => {
+                for _ in 0..tiles {
+                    let (next, turned) = match pos + facing {
+                        next if on_board(&notes.board, next) => (next, facing),
+                        _ => wrapping.wrap(pos, facing),
+                    };
+                    if notes.board[next] == Tile::Wall {
+                        break;
+                    }
+                    (pos, facing) = (next, turned);
+                }
+            }
+        }
+    }
+
+    let facing = [Vec2::RIGHT, Vec2::DOWN, Vec2::LEFT, Vec2::UP]
+        .iter()
+        .position(|&dir| dir == facing)
+        .unwrap() as i32;
+    1000 * (pos.y + 1) + 4 * (pos.x + 1) + facing
+}
+
+// "10R5L5R10L4R5L5"
+fn path(line: &str) -> Result<Vec<Step>, RecordError> {
+    let mut path = Vec::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (step, left) = match rest.split_at(digits) {
+            ("", turn) if turn.starts_with('L') => (Step::Left, &turn[1..]),
+            ("", turn) if turn.starts_with('R') => (Step::Right, &turn[1..]),
+            ("", turn) => {
+                let c = turn.chars().next().unwrap();
+                return Err(RecordError(format!("{:?} isn't a turn", c)));
+            }
+            (tiles, left) => (Step::Forward(tiles.parse()?), left),
+        };
+        path.push(step);
+        rest = left;
+    }
+    Ok(path)
+}
+
+fn parse(raw: &str) -> Result<Notes, ParseError> {
+    let blocks = parser::blocks(raw);
+    let [board_block, path_block] = &blocks[..] else {
+        return Err(ParseError::new(
+            1,
+            raw.lines().next().unwrap_or_default(),
+            RecordError(format!(
+                "expected the board and the path, got {} blocks",
+                blocks.len()
+            )),
+        ));
+    };
+
+    // lines stop at the last tile, so the rest is filled in with void
+    let width = board_block
+        .text
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut rows = Vec::new();
+    for (i, line) in board_block.text.lines().enumerate() {
+        let mut row = line
+            .chars()
+            .map(|c| match c {
+                ' ' => Ok(Tile::Void),
+                '.' => Ok(Tile::Open),
+                '#' => Ok(Tile::Wall),
+                c => Err(RecordError(format!("{:?} isn't a tile", c))),
+            })
+            .collect::<Result<Vec<Tile>, RecordError>>()
+            .map_err(|err| ParseError::new(board_block.line + i, line, err))?;
+        row.resize(width, Tile::Void);
+        rows.push(row);
+    }
+    let board = Grid::from_rows(rows).map_err(|err| err.in_input(raw))?;
+
+    let cube = Cube::fold(&board);
+    let path = path_block.parse_with(|text| path(text.trim()))?;
+    Ok(Notes { board, path, cube })
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Notes;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(raw: &str) -> Result<Notes, ParseError> {
+        parse(raw)
+    }
+
+    fn part1(notes: &Notes) -> i32 {
+        walk(
+            notes,
+            &Flat {
+                board: &notes.board,
+            },
+        )
+    }
+
+    fn part2(notes: &Notes) -> i32 {
+        let cube = notes.cube.as_ref().unwrap_or_else(|err| panic!("{}", err));
+        walk(notes, cube)
+    }
 }
@@ -1,123 +1,79 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    fs,
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    draw::Frames,
+    geometry::{Aabb, Vec2},
+    grid::{Grid, SparseGrid},
+    parser::ParseError,
+    solution::Solution,
 };
 
-const DIRECTIONS: [(i32, i32); 8] = [
-    (-1, -1),
-    (-1, 0),
-    (-1, 1),
-    (0, -1),
-    (0, 1),
-    (1, -1),
-    (1, 0),
-    (1, 1),
-];
-
-enum Dir {
-    NW = 0,
-    W = 1,
-    SW = 2,
-    N = 3,
-    S = 4,
-    NE = 5,
-    E = 6,
-    SE = 7,
-}
-
-fn extents(elves: &HashSet<(i32, i32)>) -> ((i32, i32), (i32, i32)) {
-    let mut mins = (1000, 1000);
-    let mut maxs = (0, 0);
-
-    for elf in elves {
-        mins.0 = mins.0.min(elf.0);
-        mins.1 = mins.1.min(elf.1);
-        maxs.0 = maxs.0.max(elf.0);
-        maxs.1 = maxs.1.max(elf.1);
-    }
+const ELF: char = '#';
 
-    (mins, maxs)
+fn bounds(elves: &SparseGrid<char>) -> Aabb<2> {
+    Aabb::from_points(elves.positions()).expect("no elves")
 }
 
-fn make_checks() -> VecDeque<[(i32, i32); 3]> {
-    let mut checks = VecDeque::new();
-    checks.push_back([
-        DIRECTIONS[Dir::N as usize],
-        DIRECTIONS[Dir::NE as usize],
-        DIRECTIONS[Dir::NW as usize],
-    ]);
-    checks.push_back([
-        DIRECTIONS[Dir::S as usize],
-        DIRECTIONS[Dir::SE as usize],
-        DIRECTIONS[Dir::SW as usize],
-    ]);
-    checks.push_back([
-        DIRECTIONS[Dir::W as usize],
-        DIRECTIONS[Dir::NW as usize],
-        DIRECTIONS[Dir::SW as usize],
-    ]);
-    checks.push_back([
-        DIRECTIONS[Dir::E as usize],
-        DIRECTIONS[Dir::NE as usize],
-        DIRECTIONS[Dir::SE as usize],
-    ]);
-    checks
+// the direction to move in first, then the ones either side of it that also have to be clear
+fn make_checks() -> VecDeque<[Vec2; 3]> {
+    VecDeque::from([
+        [Vec2::UP, Vec2::UP_RIGHT, Vec2::UP_LEFT],
+        [Vec2::DOWN, Vec2::DOWN_RIGHT, Vec2::DOWN_LEFT],
+        [Vec2::LEFT, Vec2::UP_LEFT, Vec2::DOWN_LEFT],
+        [Vec2::RIGHT, Vec2::UP_RIGHT, Vec2::DOWN_RIGHT],
+    ])
 }
 
-fn print_elves(elves: &HashSet<(i32, i32)>) {
-    let ((min_x, min_y), (max_x, max_y)) = extents(&elves);
+fn draw_elves(elves: &SparseGrid<char>, window: Aabb<2>) -> Grid<char> {
+    elves.to_grid(window, '.')
+}
 
-    for j in min_y..max_y + 1 {
-        for i in min_x..max_x + 1 {
-            match elves.contains(&(i, j)) {
-                true => print!("#"),
-                false => print!("."),
-            }
-        }
-        println!();
-    }
-    println!();
+// the ground the elves cover at one point or another while they spread out, so that
+// every round can be drawn the same size
+fn spread_window(elves: &SparseGrid<char>, max_rounds: Option<i32>) -> Aabb<2> {
+    let mut window = bounds(elves);
+    spread(elves, max_rounds, &mut |elves, _moved| {
+        window = window.union(bounds(elves))
+    });
+    window
 }
 
-fn part1(raw: &str) {
-    let mut elves = HashSet::new();
-    for (y, line) in raw.lines().enumerate() {
-        for (x, char) in line.chars().enumerate() {
-            if char == '#' {
-                elves.insert((x as i32, y as i32));
-            }
-        }
-    }
+fn parse(raw: &str) -> SparseGrid<char> {
+    SparseGrid::parse(raw, |c| (c == ELF).then_some(ELF))
+}
 
+// spread the elves out until nobody moves, or `max_rounds` is reached. `on_round` sees
+// where they start and where they are after every round, with how many moved in it.
+fn spread(
+    elves: &SparseGrid<char>,
+    max_rounds: Option<i32>,
+    on_round: &mut dyn FnMut(&SparseGrid<char>, usize),
+) -> (SparseGrid<char>, i32) {
+    let mut elves = elves.clone();
     let mut checks = make_checks();
-    // print_elves(&elves);
+    on_round(&elves, 0);
 
     let mut round = 0;
     let mut somebody_moved = true;
-    while somebody_moved {
+    while somebody_moved && Some(round) != max_rounds {
         round += 1;
         somebody_moved = false;
         let mut propose = HashMap::new();
-        for elf in &elves {
-            let must_move = DIRECTIONS
-                .iter()
-                .any(|e| elves.contains(&(elf.0 + e.0, elf.1 + e.1)));
+        for elf in elves.positions() {
+            let must_move = elves.neighbours8(elf).next().is_some();
             if !must_move {
-                let mut proposed_movers = propose.entry(*elf).or_insert(Vec::new());
-                proposed_movers.push(*elf);
+                let proposed_movers = propose.entry(elf).or_insert(Vec::new());
+                proposed_movers.push(elf);
                 continue;
             }
 
             let mut did_move = false;
             for check in &checks {
-                let can_move = check
-                    .iter()
-                    .all(|e| !elves.contains(&(elf.0 + e.0, elf.1 + e.1)));
+                let can_move = check.iter().all(|e| !elves.contains(elf + *e));
                 if can_move {
-                    let proposed_position = (elf.0 + check[0].0, elf.1 + check[0].1);
-                    let mut proposed_movers =
-                        propose.entry(proposed_position).or_insert(Vec::new());
-                    proposed_movers.push(*elf);
+                    let proposed_position = elf + check[0];
+                    let proposed_movers = propose.entry(proposed_position).or_insert(Vec::new());
+                    proposed_movers.push(elf);
                     did_move = true;
                     somebody_moved = true;
                     break;
@@ -125,43 +81,79 @@ fn part1(raw: &str) {
             }
 
             if !did_move {
-                let mut proposed_movers = propose.entry(*elf).or_insert(Vec::new());
-                proposed_movers.push(*elf);
+                let proposed_movers = propose.entry(elf).or_insert(Vec::new());
+                proposed_movers.push(elf);
             }
         }
 
         // println!("Proposed: {:?}", propose);
-        let mut next = HashSet::new();
+        let mut next = SparseGrid::new();
+        let mut moved = 0;
         for (proposed, by) in &propose {
             if by.len() == 1 {
-                next.insert(*proposed);
+                next.insert(*proposed, ELF);
+                if by[0] != *proposed {
+                    moved += 1;
+                }
             } else {
                 for elf in by {
-                    next.insert(*elf);
+                    next.insert(*elf, ELF);
                 }
             }
         }
 
-        elves = next.clone();
+        elves = next;
         let shuffle = checks.pop_front().unwrap();
         checks.push_back(shuffle);
-        // print_elves(&elves);
-
-        if round == 10 {
-            let e = extents(&elves);
-            let area = ((e.1).0 - (e.0).0 + 1).abs() * ((e.1).1 - (e.0).1 + 1).abs();
-            let empty = area - elves.len() as i32;
-            println!("Part 1: {}", empty);
-        }
+        on_round(&elves, moved);
     }
 
-    println!("Part 2: {}", round);
+    (elves, round)
 }
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day23.example").unwrap();
-    part1(&raw);
+fn part1(elves: &SparseGrid<char>) -> i32 {
+    let (elves, _) = spread(elves, Some(10), &mut |_elves, _moved| {});
+    // the empty ground in the smallest rectangle holding every elf
+    (bounds(&elves).area() - elves.len() as i64) as i32
+}
+
+fn part2(elves: &SparseGrid<char>) -> i32 {
+    let (_, round) = spread(elves, None, &mut |_elves, _moved| {});
+    round
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = SparseGrid<char>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(raw: &str) -> Result<Self::Input, ParseError> {
+        Ok(parse(raw))
+    }
 
-    let raw = fs::read_to_string("data/day23.txt").unwrap();
-    part1(&raw);
+    fn part1(elves: &Self::Input) -> i32 {
+        part1(elves)
+    }
+
+    fn part2(elves: &Self::Input) -> i32 {
+        part2(elves)
+    }
+
+    fn draw(elves: &Self::Input, part: u8) -> Option<Grid<char>> {
+        let max_rounds = if part == 1 { Some(10) } else { None };
+        let window = spread_window(elves, max_rounds);
+        let (elves, _) = spread(elves, max_rounds, &mut |_elves, _moved| {});
+        Some(draw_elves(&elves, window))
+    }
+
+    // a frame for each round
+    fn animate(elves: &Self::Input, part: u8, frames: &mut Frames) {
+        let max_rounds = if part == 1 { Some(10) } else { None };
+        let window = spread_window(elves, max_rounds);
+        spread(elves, max_rounds, &mut |elves, moved| {
+            frames.step(&[("elves moved", moved)], || draw_elves(elves, window))
+        });
+    }
 }
@@ -1,16 +1,16 @@
-use std::{
-    collections::HashSet,
-    fs,
-    ops::{Add, Mul, Rem, Sub},
-    option,
+use std::collections::HashSet;
+
+use crate::{
+    draw::Frames,
+    geometry::Vec2,
+    grid::Grid,
+    parser::{ParseError, RecordError},
+    search,
+    solution::Solution,
 };
 
-const MOVE: [Pos; 4] = [
-    Pos { x: 0, y: -1 },
-    Pos { x: 0, y: 1 },
-    Pos { x: -1, y: 0 },
-    Pos { x: 1, y: 0 },
-];
+// indexed by `Dir`
+const MOVE: [Vec2; 4] = [Vec2::UP, Vec2::DOWN, Vec2::LEFT, Vec2::RIGHT];
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 enum Dir {
@@ -20,65 +20,15 @@ enum Dir {
     Right,
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
-struct Pos {
-    x: i32,
-    y: i32,
-}
-
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Blizzard {
-    pos: Pos,
+pub struct Blizzard {
+    pos: Vec2,
     dir: Dir,
 }
 
-impl Add for Pos {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self {
-        Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
-    }
-}
-
-impl Sub for Pos {
-    type Output = Self;
-
-    fn sub(self, other: Self) -> Self {
-        Self {
-            x: self.x - other.x,
-            y: self.y - other.y,
-        }
-    }
-}
-
-impl Rem for Pos {
-    type Output = Self;
-
-    fn rem(self, other: Self) -> Self {
-        Self {
-            x: self.x.rem_euclid(other.x),
-            y: self.y.rem_euclid(other.y),
-        }
-    }
-}
-
-impl Mul<i32> for Pos {
-    type Output = Self;
-
-    fn mul(self, scalar: i32) -> Self {
-        Self {
-            x: self.x * scalar,
-            y: self.y * scalar,
-        }
-    }
-}
-
 impl Blizzard {
-    fn at(self, minute: i32, valley: Pos) -> Pos {
-        let correction = Pos { x: 1, y: 1 };
+    fn at(self, minute: i32, valley: Vec2) -> Vec2 {
+        let correction = Vec2::new(1, 1);
         let new_pos = self.pos - correction;
         let new_pos = new_pos + MOVE[self.dir as usize] * minute;
         let new_pos = new_pos % valley;
@@ -86,49 +36,72 @@ impl Blizzard {
     }
 }
 
-fn parse(raw: &str) -> (Pos, HashSet<Blizzard>) {
-    let mut blizzards = HashSet::new();
-    let mut valley = Pos { x: 0, y: 0 };
-    for (y, line) in raw.lines().enumerate() {
-        for (x, char) in line.chars().enumerate() {
-            let dir: Option<Dir> = match char {
-                '^' => Some(Dir::Up),
-                'v' => Some(Dir::Down),
-                '<' => Some(Dir::Left),
-                '>' => Some(Dir::Right),
-                _ => None,
-            };
-            match dir {
-                Some(dir) => {
-                    let pos = Pos {
-                        x: x as i32,
-                        y: y as i32,
-                    };
-                    blizzards.insert(Blizzard { pos, dir });
-                }
-                None => (),
-            }
-            valley.x = valley.x.max(x as i32);
+fn parse(raw: &str) -> Result<(Vec2, HashSet<Blizzard>), ParseError> {
+    let chars = Grid::parse(raw, |c| c).map_err(|err| err.in_input(raw))?;
+    let error = |pos: Vec2, message: String| {
+        let line = raw.lines().nth(pos.y as usize).unwrap_or_default();
+        ParseError::new(pos.y as usize + 1, line, RecordError(message))
+    };
+
+    if let Some((pos, c)) = chars
+        .iter()
+        .find(|(_pos, c)| !matches!(c, '#' | '.' | '^' | 'v' | '<' | '>'))
+    {
+        return Err(error(
+            pos,
+            format!("{:?} in column {} isn't part of a valley", c, pos.x + 1),
+        ));
+    }
+    let (width, height) = (chars.width() as i32, chars.height() as i32);
+    if width < 3 || height < 3 {
+        return Err(error(
+            Vec2::ZERO,
+            format!("a {}x{} valley has no room inside its walls", width, height),
+        ));
+    }
+    // the way in is at the left of the top wall, and the way out at the right of the bottom
+    for (pos, name) in [
+        (Vec2::new(1, 0), "entrance"),
+        (Vec2::new(width - 2, height - 1), "exit"),
+    ] {
+        if chars[pos] != '.' {
+            return Err(error(
+                pos,
+                format!("the {} in column {} isn't open", name, pos.x + 1),
+            ));
         }
-        valley.y = valley.y.max(y as i32);
     }
-    let valley = valley - Pos { x: 1, y: 1 };
-    (valley, blizzards)
+
+    let map = chars.map(|&c| match c {
+        '^' => Some(Dir::Up),
+        'v' => Some(Dir::Down),
+        '<' => Some(Dir::Left),
+        '>' => Some(Dir::Right),
+        _ => None,
+    });
+
+    let blizzards = map
+        .iter()
+        .filter_map(|(pos, dir)| Some(Blizzard { pos, dir: (*dir)? }))
+        .collect();
+    // the inside of the walls
+    let valley = Vec2::new(map.width() as i32 - 2, map.height() as i32 - 2);
+    Ok((valley, blizzards))
 }
 
-fn all_moves(elf_pos: Pos, blizzards: &HashSet<Pos>, valley: Pos, target: Pos) -> Vec<Pos> {
+fn all_moves(elf_pos: Vec2, blizzards: &Grid<bool>, valley: Vec2, target: Vec2) -> Vec<Vec2> {
     let mut move_options = Vec::new();
 
-    if !blizzards.contains(&elf_pos) {
+    if blizzards.get(elf_pos) != Some(&true) {
         move_options.push(elf_pos); // can stay where you are
     }
-    for dir in 0..4 {
-        let possible = elf_pos + MOVE[dir];
+    for dir in MOVE {
+        let possible = elf_pos + dir;
         if possible == target {
             move_options.push(possible);
             return move_options; // short-circuit... who cares about the rest.
         }
-        if blizzards.contains(&possible) {
+        if blizzards.get(possible) == Some(&true) {
             continue;
         }
         if possible.x < 1 || possible.x > valley.x || possible.y < 1 || possible.y > valley.y {
@@ -140,87 +113,165 @@ fn all_moves(elf_pos: Pos, blizzards: &HashSet<Pos>, valley: Pos, target: Pos) -
     move_options
 }
 
-fn draw_valley(valley: Pos, blizzards: &HashSet<Pos>) {
-    for y in 0..valley.y + 2 {
-        for x in 0..valley.x + 2 {
-            if x < 1 || x > valley.x || y < 1 || y > valley.y {
-                print!("#");
-            } else {
-                match blizzards.get(&Pos { x, y }) {
-                    Some(b) => print!("b"),
-                    None => print!("."),
-                }
-            }
+impl Dir {
+    fn symbol(self) -> char {
+        match self {
+            Dir::Up => '^',
+            Dir::Down => 'v',
+            Dir::Left => '<',
+            Dir::Right => '>',
         }
-        println!()
     }
-    println!()
 }
 
-fn traverse(
-    starting_move: i32,
-    start: Pos,
-    target: Pos,
+// the valley as the puzzle draws it, with a count where blizzards overlap and `E` for
+// the expedition
+fn draw_valley(
+    valley: Vec2,
     initial: &HashSet<Blizzard>,
-    valley: Pos,
-) -> i32 {
-    let mut current = Vec::new();
-    current.push(start);
-
-    let mut minute = starting_move;
-    while true {
-        minute += 1;
-        let blizzards: HashSet<Pos> =
-            HashSet::from_iter(initial.iter().map(|b| b.at(minute, valley)));
-        let mut next = Vec::new();
-        for elf_pos in &current {
-            next.extend(all_moves(*elf_pos, &blizzards, valley, target));
-        }
-        if next.contains(&target) {
-            break;
+    minute: i32,
+    expedition: Vec2,
+) -> Grid<char> {
+    let mut picture = Grid::new(valley.x as usize + 2, valley.y as usize + 2, '#');
+    for y in 1..=valley.y {
+        for x in 1..=valley.x {
+            picture[Vec2::new(x, y)] = '.';
         }
-        next.sort();
-        next.dedup();
-        current = next.clone();
     }
+    picture[Vec2::new(1, 0)] = '.';
+    picture[Vec2::new(valley.x, valley.y + 1)] = '.';
 
-    minute
+    for blizzard in initial {
+        let cell = &mut picture[blizzard.at(minute, valley)];
+        *cell = match *cell {
+            '.' => blizzard.dir.symbol(),
+            '2'..='8' => (*cell as u8 + 1) as char,
+            '9' => '9',
+            _ => '2',
+        };
+    }
+    picture[expedition] = 'E';
+    picture
 }
 
-fn part1(raw: &str) {
-    let (valley, initial) = parse(&raw);
+// the blizzards are back where they started every `period` minutes, so that's all the
+// maps there are
+fn blizzard_maps(initial: &HashSet<Blizzard>, valley: Vec2) -> Vec<Grid<bool>> {
+    let period = valley.x * valley.y / gcd(valley.x, valley.y);
+    (0..period)
+        .map(|minute| {
+            // the whole map, walls included, so positions index it directly
+            let mut blizzards = Grid::new(valley.x as usize + 2, valley.y as usize + 2, false);
+            for blizzard in initial {
+                blizzards[blizzard.at(minute, valley)] = true;
+            }
+            blizzards
+        })
+        .collect()
+}
 
-    let elves = Pos { x: 1, y: 0 };
-    let target = Pos {
-        x: valley.x,
-        y: valley.y + 1,
-    };
-    let result = traverse(0, elves, target, &initial, valley);
+fn gcd(a: i32, b: i32) -> i32 {
+    match b {
+        0 => a,
+        b => gcd(b, a % b),
+    }
+}
 
-    println!("Part 1: {}", result);
+// where the expedition is each minute of the quickest way from `start` to `target`,
+// setting off at `starting_move`
+fn traverse(
+    starting_move: i32,
+    start: Vec2,
+    target: Vec2,
+    maps: &[Grid<bool>],
+    valley: Vec2,
+) -> Vec<Vec2> {
+    let period = maps.len() as i32;
+    // a state is a position and where the blizzards are in their cycle
+    let path = search::bfs(
+        (start, starting_move % period),
+        |&(elf_pos, minute)| {
+            let minute = (minute + 1) % period;
+            all_moves(elf_pos, &maps[minute as usize], valley, target)
+                .into_iter()
+                .map(move |next| (next, minute))
+        },
+        |&(elf_pos, _minute)| elf_pos == target,
+    )
+    .expect("no way through the valley");
+
+    path.nodes.into_iter().map(|(pos, _minute)| pos).collect()
 }
 
-fn part2(raw: &str) {
-    let (valley, initial) = parse(&raw);
+// where the expedition is each minute, from setting off to the end of the last of `trips`
+// across the valley, going back and forth, along with how many trips it's made by then
+fn expedition(input: &(Vec2, HashSet<Blizzard>), trips: usize) -> Vec<(Vec2, usize)> {
+    let (valley, initial) = input;
+    let valley = *valley;
 
-    let elves = Pos { x: 1, y: 0 };
-    let target = Pos {
-        x: valley.x,
-        y: valley.y + 1,
-    };
-    let result = traverse(0, elves, target, &initial, valley);
-    let result = traverse(result, target, elves, &initial, valley);
-    let result = traverse(result, elves, target, &initial, valley);
+    let maps = blizzard_maps(initial, valley);
+
+    let mut start = Vec2::new(1, 0);
+    let mut target = Vec2::new(valley.x, valley.y + 1);
+    let mut route = vec![(start, 0)];
+    for trip in 0..trips {
+        let minute = route.len() as i32 - 1;
+        let path = traverse(minute, start, target, &maps, valley);
+        route.extend(path[1..].iter().map(|&pos| (pos, trip)));
+        route.last_mut().unwrap().1 = trip + 1;
+        (start, target) = (target, start);
+    }
+    route
+}
+
+fn part1(input: &(Vec2, HashSet<Blizzard>)) -> i32 {
+    expedition(input, 1).len() as i32 - 1
+}
 
-    println!("Part 2: {}", result);
+fn part2(input: &(Vec2, HashSet<Blizzard>)) -> i32 {
+    expedition(input, 3).len() as i32 - 1
 }
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day24.example").unwrap();
-    part1(&raw);
-    part2(&raw);
+pub struct Day24;
 
-    let raw = fs::read_to_string("data/day24.txt").unwrap();
-    part1(&raw);
-    part2(&raw);
+impl Solution for Day24 {
+    type Input = (Vec2, HashSet<Blizzard>);
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(raw: &str) -> Result<Self::Input, ParseError> {
+        parse(raw)
+    }
+
+    fn part1(input: &Self::Input) -> i32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> i32 {
+        part2(input)
+    }
+
+    // where the blizzards are as the expedition reaches the far side for the last time
+    fn draw(input: &Self::Input, part: u8) -> Option<Grid<char>> {
+        let (valley, initial) = input;
+        let route = expedition(input, if part == 1 { 1 } else { 3 });
+        let minute = route.len() - 1;
+        Some(draw_valley(
+            *valley,
+            initial,
+            minute as i32,
+            route[minute].0,
+        ))
+    }
+
+    // a frame for each minute
+    fn animate(input: &Self::Input, part: u8, frames: &mut Frames) {
+        let (valley, initial) = input;
+        let route = expedition(input, if part == 1 { 1 } else { 3 });
+        for (minute, &(pos, trips)) in route.iter().enumerate() {
+            frames.step(&[("trips made", trips)], || {
+                draw_valley(*valley, initial, minute as i32, pos)
+            });
+        }
+    }
 }
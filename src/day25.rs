@@ -1,4 +1,7 @@
-use std::fs;
+use crate::{
+    parser::ParseError,
+    solution::{Pending, Solution},
+};
 
 fn to_snafu(val: i64) -> String {
     let mut v = Vec::new();
@@ -27,6 +30,7 @@ fn to_snafu(val: i64) -> String {
     v.join("")
 }
 
+#[allow(dead_code)]
 fn to_base5v1(val: i64) -> String {
     let mut v = Vec::new();
 
@@ -47,6 +51,7 @@ fn to_base5v1(val: i64) -> String {
     v.join("")
 }
 
+#[allow(dead_code)]
 fn to_base5v2(val: i64) -> String {
     let mut v = Vec::new();
 
@@ -73,9 +78,9 @@ fn from_snafu(val: &str) -> i64 {
         let place = 5i64.pow(place as u32);
         sum += match char {
             '2' => 2 * place,
-            '1' => 1 * place,
-            '0' => 0 * place,
-            '-' => -1 * place,
+            '1' => place,
+            '0' => 0,
+            '-' => -place,
             '=' => -2 * place,
             _ => panic!("invalid SNAFU digit"),
         }
@@ -84,14 +89,22 @@ fn from_snafu(val: &str) -> i64 {
     sum
 }
 
-fn snafu_sum(raw: &str) -> String {
-    to_snafu(raw.lines().map(|s| from_snafu(s)).sum::<i64>())
-}
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = Vec<i64>;
+    type Answer1 = String;
+    type Answer2 = Pending;
+
+    fn parse(raw: &str) -> Result<Vec<i64>, ParseError> {
+        Ok(raw.lines().map(from_snafu).collect())
+    }
 
-pub fn solve() {
-    let raw = fs::read_to_string("data/day25.example").unwrap();
-    println!("Sum: {}", snafu_sum(&raw));
+    fn part1(values: &Vec<i64>) -> String {
+        to_snafu(values.iter().sum())
+    }
 
-    let raw = fs::read_to_string("data/day25.txt").unwrap();
-    println!("Sum: {}", snafu_sum(&raw));
+    fn part2(_values: &Vec<i64>) -> Pending {
+        Pending
+    }
 }
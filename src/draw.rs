@@ -0,0 +1,203 @@
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+
+use crate::{
+    grid::Grid,
+    image::{self, ImageFormat, Palette, Rgb},
+    solution::Solution,
+    Days, Selection,
+};
+
+/// Where pictures and animations are saved, and how.
+#[derive(Args)]
+pub struct DrawOptions {
+    /// Also save each picture as an image in this directory
+    #[arg(long)]
+    pub image: Option<PathBuf>,
+    /// Save a frame for every step of the simulation in this directory
+    #[arg(long)]
+    pub animate: Option<PathBuf>,
+    /// Only save every this many steps when animating, to keep long runs manageable
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub stride: u32,
+    /// Format for saved images
+    #[arg(long = "image-format", value_enum, default_value_t = ImageFormat::Png)]
+    pub format: ImageFormat,
+    /// Colours to change from the default palette, as c=rrggbb pairs, e.g. '#=000000,.=ffffff'
+    #[arg(long, value_parser = Palette::parse, default_value = "")]
+    pub palette: Palette,
+    /// Pixels per cell
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    pub scale: u16,
+}
+
+impl DrawOptions {
+    fn save(
+        &self,
+        picture: &Grid<char>,
+        backdrop: Option<&Grid<Rgb>>,
+        path: &Path,
+    ) -> Result<(), String> {
+        image::save(
+            picture,
+            backdrop,
+            path,
+            self.format,
+            &self.palette,
+            self.scale as usize,
+        )
+        .map_err(|err| format!("couldn't write {}: {}", path.display(), err))
+    }
+}
+
+/// One step of a simulation: its number, what it looks like, and how far along it is by
+/// whatever the simulation counts, e.g. units of sand.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub step: usize,
+    pub picture: Grid<char>,
+    pub counters: Vec<(&'static str, usize)>,
+}
+
+/// The steps of a simulation as it runs, handing the ones that are wanted on to `sink`.
+/// Simulations call `step` for their starting state and again after every step, and the
+/// picture is only drawn for the steps that are kept.
+pub struct Frames<'a> {
+    stride: usize,
+    wanted: Range<usize>,
+    steps: usize,
+    sink: Box<dyn FnMut(Frame) + 'a>,
+}
+
+impl<'a> Frames<'a> {
+    /// Keep every `stride`th step.
+    pub fn new(stride: usize, sink: impl FnMut(Frame) + 'a) -> Frames<'a> {
+        Frames {
+            stride,
+            wanted: 0..usize::MAX,
+            steps: 0,
+            sink: Box::new(sink),
+        }
+    }
+
+    /// Keep only the steps in `wanted`.
+    pub fn between(wanted: Range<usize>, sink: impl FnMut(Frame) + 'a) -> Frames<'a> {
+        Frames {
+            stride: 1,
+            wanted,
+            steps: 0,
+            sink: Box::new(sink),
+        }
+    }
+
+    pub fn step(&mut self, counters: &[(&'static str, usize)], draw: impl FnOnce() -> Grid<char>) {
+        if self.wanted.contains(&self.steps) && self.steps.is_multiple_of(self.stride) {
+            (self.sink)(Frame {
+                step: self.steps,
+                picture: draw(),
+                counters: counters.to_vec(),
+            });
+        }
+        self.steps += 1;
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+}
+
+/// Print the picture `S` draws of each selected input and part, saving it as an image too
+/// if asked, and save a frame for each step of the simulation behind it if asked.
+pub fn draw<S: Solution>(
+    day: &Days,
+    selection: &Selection,
+    options: &DrawOptions,
+) -> Result<(), String> {
+    for dir in options.image.iter().chain(&options.animate) {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("couldn't create {}: {}", dir.display(), err))?;
+    }
+
+    for (input, filename) in selection.inputs(day, S::INPUTS) {
+        let raw = crate::read_input(&filename)
+            .map_err(|err| format!("couldn't read {}: {}", filename, err))?;
+        let parsed = S::parse_input(&input, &raw)
+            .map_err(|err| err.in_file(crate::input_path(&filename)).to_string())?;
+
+        for part in [1, 2] {
+            if selection.part.is_some_and(|selected| selected != part) {
+                continue;
+            }
+            let Some(picture) = S::draw(&parsed, part) else {
+                return Err(format!("{:?} has nothing to draw", day));
+            };
+            println!("{} part {}:", input, part);
+            print!("{}", picture);
+
+            if let Some(dir) = &options.image {
+                let name = format!("{}.part{}.{}", input, part, options.format.extension());
+                let path = dir.join(name);
+                options.save(&picture, S::backdrop(&parsed, part).as_ref(), &path)?;
+                println!("saved {}", path.display());
+            }
+
+            if let Some(dir) = &options.animate {
+                let name = format!("{}.part{}", input, part);
+                animate::<S>(day, &parsed, part, &picture, &dir.join(name), options)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// save the frames as `prefix.NNNNNN.ext`, numbered by step. The last step is always saved,
+// whatever the stride, and it's the same as the finished `picture`.
+fn animate<S: Solution>(
+    day: &Days,
+    input: &S::Input,
+    part: u8,
+    picture: &Grid<char>,
+    prefix: &Path,
+    options: &DrawOptions,
+) -> Result<(), String> {
+    let frame_path = |step: usize| {
+        let mut path = prefix.as_os_str().to_owned();
+        path.push(format!(".{:06}.{}", step, options.format.extension()));
+        PathBuf::from(path)
+    };
+
+    let mut saved = 0;
+    // the first error stops any more frames being written
+    let mut result = Ok(());
+    let mut frames = Frames::new(options.stride as usize, |frame| {
+        if result.is_ok() {
+            result = options.save(&frame.picture, None, &frame_path(frame.step));
+            saved += 1;
+        }
+    });
+    S::animate(input, part, &mut frames);
+    let steps = frames.steps();
+    drop(frames);
+    result?;
+
+    if steps == 0 {
+        return Err(format!("{:?} has nothing to animate", day));
+    }
+    if !(steps - 1).is_multiple_of(options.stride as usize) {
+        options.save(picture, None, &frame_path(steps - 1))?;
+        saved += 1;
+    }
+    println!(
+        "saved {} frames of {} steps as {}.*",
+        saved,
+        steps,
+        prefix.display()
+    );
+    Ok(())
+}
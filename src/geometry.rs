@@ -0,0 +1,502 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+};
+
+/// The signed number types vectors are made of.
+pub trait Scalar:
+    Copy
+    + Ord
+    + Default
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
+macro_rules! scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+
+                fn rem_euclid(self, rhs: Self) -> Self {
+                    <$t>::rem_euclid(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+scalar!(i8, i16, i32, i64, i128, isize);
+
+/// A point or offset on a grid, `x` across and `y` down, the way the puzzles draw maps.
+/// Positions are signed so that stepping off an edge gives a position a grid can turn
+/// down, rather than an underflow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A point or offset in space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec3<T = i32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Vec2<T> {
+        Vec2 { x, y }
+    }
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Vec3<T> {
+        Vec3 { x, y, z }
+    }
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2::new(0, 0);
+    pub const UP: Vec2 = Vec2::new(0, -1);
+    pub const DOWN: Vec2 = Vec2::new(0, 1);
+    pub const LEFT: Vec2 = Vec2::new(-1, 0);
+    pub const RIGHT: Vec2 = Vec2::new(1, 0);
+    pub const UP_LEFT: Vec2 = Vec2::new(-1, -1);
+    pub const UP_RIGHT: Vec2 = Vec2::new(1, -1);
+    pub const DOWN_LEFT: Vec2 = Vec2::new(-1, 1);
+    pub const DOWN_RIGHT: Vec2 = Vec2::new(1, 1);
+
+    /// Up first, then clockwise.
+    pub const ORTHOGONAL: [Vec2; 4] = [Vec2::UP, Vec2::RIGHT, Vec2::DOWN, Vec2::LEFT];
+    /// Row by row from the top left.
+    pub const NEIGHBOURS: [Vec2; 8] = [
+        Vec2::UP_LEFT,
+        Vec2::UP,
+        Vec2::UP_RIGHT,
+        Vec2::LEFT,
+        Vec2::RIGHT,
+        Vec2::DOWN_LEFT,
+        Vec2::DOWN,
+        Vec2::DOWN_RIGHT,
+    ];
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3::new(0, 0, 0);
+    pub const X: Vec3 = Vec3::new(1, 0, 0);
+    pub const Y: Vec3 = Vec3::new(0, 1, 0);
+    pub const Z: Vec3 = Vec3::new(0, 0, 1);
+
+    /// One step through each face of a unit cube.
+    pub const ORTHOGONAL: [Vec3; 6] = [
+        Vec3::X,
+        Vec3::new(-1, 0, 0),
+        Vec3::Y,
+        Vec3::new(0, -1, 0),
+        Vec3::Z,
+        Vec3::new(0, 0, -1),
+    ];
+}
+
+impl<T: Scalar> Vec2<T> {
+    pub fn signum(self) -> Vec2<T> {
+        Vec2::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn abs(self) -> Vec2<T> {
+        Vec2::new(self.x.abs(), self.y.abs())
+    }
+
+    pub fn manhattan(self, other: Vec2<T>) -> T {
+        let d = (self - other).abs();
+        d.x + d.y
+    }
+
+    /// Distance counting diagonal steps as one, like a king on a chessboard.
+    pub fn chebyshev(self, other: Vec2<T>) -> T {
+        let d = (self - other).abs();
+        d.x.max(d.y)
+    }
+
+    /// A quarter turn clockwise, as seen on a map with `y` down.
+    pub fn rotate_right(self) -> Vec2<T> {
+        Vec2::new(-self.y, self.x)
+    }
+
+    /// A quarter turn anticlockwise, as seen on a map with `y` down.
+    pub fn rotate_left(self) -> Vec2<T> {
+        Vec2::new(self.y, -self.x)
+    }
+}
+
+impl<T: Scalar> Vec3<T> {
+    pub fn signum(self) -> Vec3<T> {
+        Vec3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    pub fn abs(self) -> Vec3<T> {
+        Vec3::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    pub fn manhattan(self, other: Vec3<T>) -> T {
+        let d = (self - other).abs();
+        d.x + d.y + d.z
+    }
+
+    pub fn chebyshev(self, other: Vec3<T>) -> T {
+        let d = (self - other).abs();
+        d.x.max(d.y).max(d.z)
+    }
+
+    pub fn dot(self, other: Vec3<T>) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    // quarter turns about each axis, right-handed: anticlockwise looking down the axis
+    pub fn rotate_x(self) -> Vec3<T> {
+        Vec3::new(self.x, -self.z, self.y)
+    }
+
+    pub fn rotate_y(self) -> Vec3<T> {
+        Vec3::new(self.z, self.y, -self.x)
+    }
+
+    pub fn rotate_z(self) -> Vec3<T> {
+        Vec3::new(-self.y, self.x, self.z)
+    }
+}
+
+impl<T> From<(T, T)> for Vec2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Vec2::new(x, y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Vec3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Vec3::new(x, y, z)
+    }
+}
+
+// component-wise operators for both vector types, plus scaling by a scalar. `%` is
+// Euclidean, so wrapping a position around a grid never goes negative.
+macro_rules! operators {
+    ($vec:ident { $($field:ident),* }) => {
+        impl<T: Scalar> Add for $vec<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $vec { $($field: self.$field + other.$field),* }
+            }
+        }
+
+        impl<T: Scalar> Sub for $vec<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $vec { $($field: self.$field - other.$field),* }
+            }
+        }
+
+        impl<T: Scalar> Mul<T> for $vec<T> {
+            type Output = Self;
+
+            fn mul(self, scalar: T) -> Self {
+                $vec { $($field: self.$field * scalar),* }
+            }
+        }
+
+        impl<T: Scalar> Div<T> for $vec<T> {
+            type Output = Self;
+
+            fn div(self, scalar: T) -> Self {
+                $vec { $($field: self.$field / scalar),* }
+            }
+        }
+
+        impl<T: Scalar> Rem for $vec<T> {
+            type Output = Self;
+
+            fn rem(self, other: Self) -> Self {
+                $vec { $($field: self.$field.rem_euclid(other.$field)),* }
+            }
+        }
+
+        impl<T: Scalar> Neg for $vec<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $vec { $($field: -self.$field),* }
+            }
+        }
+
+        impl<T: Scalar> AddAssign for $vec<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Scalar> SubAssign for $vec<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl<T: Scalar> MulAssign<T> for $vec<T> {
+            fn mul_assign(&mut self, scalar: T) {
+                *self = *self * scalar;
+            }
+        }
+    };
+}
+
+operators!(Vec2 { x, y });
+operators!(Vec3 { x, y, z });
+
+/// Points an `Aabb<N>` can be built from and iterate over.
+pub trait Coords<const N: usize>: Copy {
+    fn coords(self) -> [i32; N];
+    fn from_coords(coords: [i32; N]) -> Self;
+}
+
+impl Coords<2> for Vec2 {
+    fn coords(self) -> [i32; 2] {
+        [self.x, self.y]
+    }
+
+    fn from_coords([x, y]: [i32; 2]) -> Self {
+        Vec2::new(x, y)
+    }
+}
+
+impl Coords<3> for Vec3 {
+    fn coords(self) -> [i32; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    fn from_coords([x, y, z]: [i32; 3]) -> Self {
+        Vec3::new(x, y, z)
+    }
+}
+
+/// An axis-aligned bounding box in `N` dimensions, inclusive at both ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aabb<const N: usize> {
+    pub min: [i32; N],
+    pub max: [i32; N],
+}
+
+impl<const N: usize> Aabb<N> {
+    pub const fn new(min: [i32; N], max: [i32; N]) -> Aabb<N> {
+        Aabb { min, max }
+    }
+
+    /// The box around a single point.
+    pub fn around(point: impl Coords<N>) -> Aabb<N> {
+        let coords = point.coords();
+        Aabb::new(coords, coords)
+    }
+
+    /// The smallest box holding all of `points`, or `None` if there aren't any.
+    pub fn from_points<P: Coords<N>>(points: impl IntoIterator<Item = P>) -> Option<Aabb<N>> {
+        let mut points = points.into_iter();
+        let mut aabb = Aabb::around(points.next()?);
+        for point in points {
+            aabb.grow(point);
+        }
+        Some(aabb)
+    }
+
+    /// Stretch the box to take in `point`.
+    pub fn grow(&mut self, point: impl Coords<N>) {
+        for (axis, c) in point.coords().into_iter().enumerate() {
+            self.min[axis] = self.min[axis].min(c);
+            self.max[axis] = self.max[axis].max(c);
+        }
+    }
+
+    pub fn union(mut self, other: Aabb<N>) -> Aabb<N> {
+        for axis in 0..N {
+            self.min[axis] = self.min[axis].min(other.min[axis]);
+            self.max[axis] = self.max[axis].max(other.max[axis]);
+        }
+        self
+    }
+
+    /// The box with a border `by` cells thick added all round.
+    pub fn expand(mut self, by: i32) -> Aabb<N> {
+        for axis in 0..N {
+            self.min[axis] -= by;
+            self.max[axis] += by;
+        }
+        self
+    }
+
+    pub fn contains(&self, point: impl Coords<N>) -> bool {
+        let coords = point.coords();
+        (0..N).all(|axis| (self.min[axis]..=self.max[axis]).contains(&coords[axis]))
+    }
+
+    /// Whether `point` is inside the box and on one of its faces.
+    pub fn on_boundary(&self, point: impl Coords<N>) -> bool {
+        let coords = point.coords();
+        self.contains(point)
+            && (0..N).any(|axis| coords[axis] == self.min[axis] || coords[axis] == self.max[axis])
+    }
+
+    /// How many cells the box spans along each axis.
+    pub fn size(&self) -> [i32; N] {
+        let mut size = [0; N];
+        for (axis, s) in size.iter_mut().enumerate() {
+            *s = self.max[axis] - self.min[axis] + 1;
+        }
+        size
+    }
+
+    /// How many cells are in the box: its area in 2D, or volume in 3D.
+    pub fn cell_count(&self) -> i64 {
+        self.size().iter().map(|&s| s as i64).product()
+    }
+
+    /// Every cell in the box, with the first axis changing fastest, so row by row in 2D.
+    pub fn cells<P: Coords<N>>(&self) -> impl Iterator<Item = P> {
+        let (min, size) = (self.min, self.size());
+        (0..self.cell_count()).map(move |mut i| {
+            let mut coords = min;
+            for axis in 0..N {
+                coords[axis] += (i % size[axis] as i64) as i32;
+                i /= size[axis] as i64;
+            }
+            P::from_coords(coords)
+        })
+    }
+}
+
+impl Aabb<2> {
+    pub fn area(&self) -> i64 {
+        self.cell_count()
+    }
+}
+
+impl Aabb<3> {
+    pub fn volume(&self) -> i64 {
+        self.cell_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vec2_distances_and_turns() {
+        let a = Vec2::new(1, -2);
+        let b = Vec2::new(-3, 4);
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!((b - a).signum(), Vec2::new(-1, 1));
+        assert_eq!((b - a).abs(), Vec2::new(4, 6));
+        // with y down, right of up is right, and left of up is left
+        assert_eq!(Vec2::UP.rotate_right(), Vec2::RIGHT);
+        assert_eq!(Vec2::UP.rotate_left(), Vec2::LEFT);
+        assert_eq!(a.rotate_right().rotate_left(), a);
+    }
+
+    #[test]
+    fn vec2_operators() {
+        let a = Vec2::new(5, -7);
+        assert_eq!(a + Vec2::new(1, 2), Vec2::new(6, -5));
+        assert_eq!(a - Vec2::new(1, 2), Vec2::new(4, -9));
+        assert_eq!(a * 2, Vec2::new(10, -14));
+        assert_eq!(-a, Vec2::new(-5, 7));
+        // Euclidean, so it never goes negative
+        assert_eq!(a % Vec2::new(3, 3), Vec2::new(2, 2));
+    }
+
+    #[test]
+    fn vec3_distances() {
+        let a = Vec3::new(1, -2, 3);
+        let b = Vec3::new(-1, 2, 0);
+        assert_eq!(a.manhattan(b), 9);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!((b - a).signum(), Vec3::new(-1, 1, -1));
+        assert_eq!((b - a).abs(), Vec3::new(2, 4, 3));
+        assert_eq!(a.manhattan(Vec3::ZERO), 6);
+    }
+
+    #[test]
+    fn vec3_cross_is_right_handed() {
+        assert_eq!(Vec3::X.cross(Vec3::Y), Vec3::Z);
+        assert_eq!(Vec3::Y.cross(Vec3::Z), Vec3::X);
+        assert_eq!(Vec3::Z.cross(Vec3::X), Vec3::Y);
+        assert_eq!(Vec3::Y.cross(Vec3::X), -Vec3::Z);
+        let (a, b) = (Vec3::new(1, 2, 3), Vec3::new(-4, 5, 6));
+        assert_eq!(a.cross(b).dot(a), 0);
+        assert_eq!(a.cross(b).dot(b), 0);
+    }
+
+    #[test]
+    fn vec3_rotations_turn_anticlockwise_about_their_axis() {
+        assert_eq!(Vec3::Y.rotate_x(), Vec3::Z);
+        assert_eq!(Vec3::Z.rotate_y(), Vec3::X);
+        assert_eq!(Vec3::X.rotate_z(), Vec3::Y);
+        let a = Vec3::new(1, 2, 3);
+        assert_eq!(a.rotate_x().x, a.x);
+        assert_eq!(a.rotate_y().y, a.y);
+        assert_eq!(a.rotate_z().z, a.z);
+        assert_eq!(a.rotate_x().rotate_x().rotate_x().rotate_x(), a);
+        assert_eq!(a.rotate_y().rotate_y().rotate_y().rotate_y(), a);
+        assert_eq!(a.rotate_z().rotate_z().rotate_z().rotate_z(), a);
+    }
+
+    #[test]
+    fn aabb_area_and_cells() {
+        let bounds = Aabb::from_points([Vec2::new(2, -1), Vec2::new(0, 1)]).unwrap();
+        assert_eq!(bounds.size(), [3, 3]);
+        assert_eq!(bounds.area(), 9);
+        assert!(bounds.contains(Vec2::new(1, 0)));
+        assert!(!bounds.on_boundary(Vec2::new(1, 0)));
+        assert!(bounds.on_boundary(Vec2::new(2, 0)));
+        assert!(!bounds.contains(Vec2::new(3, 0)));
+        let cells: Vec<Vec2> = bounds.cells().collect();
+        assert_eq!(cells.len(), 9);
+        assert_eq!(cells[0], Vec2::new(0, -1));
+        assert_eq!(cells[1], Vec2::new(1, -1));
+    }
+
+    #[test]
+    fn aabb_volume() {
+        let mut bounds = Aabb::around(Vec3::ZERO);
+        assert_eq!(bounds.volume(), 1);
+        bounds.grow(Vec3::new(1, 2, 3));
+        assert_eq!(bounds.volume(), 24);
+        assert_eq!(bounds.expand(1).volume(), 4 * 5 * 6);
+        let other = Aabb::around(Vec3::new(-1, 0, 0));
+        assert_eq!(bounds.union(other).volume(), 36);
+        assert_eq!(bounds.cells::<Vec3>().count() as i64, bounds.volume());
+    }
+}
@@ -0,0 +1,414 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    geometry::{Aabb, Vec2},
+    parser::ParseError,
+};
+
+/// The four orthogonal neighbours of `pos`, up first and then clockwise.
+pub fn neighbours4(pos: Vec2) -> impl Iterator<Item = Vec2> {
+    Vec2::ORTHOGONAL.into_iter().map(move |d| pos + d)
+}
+
+/// All eight neighbours of `pos`, row by row from the top left.
+pub fn neighbours8(pos: Vec2) -> impl Iterator<Item = Vec2> {
+    Vec2::NEIGHBOURS.into_iter().map(move |d| pos + d)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    /// `row` is 1-based, like a line number.
+    Ragged {
+        row: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "empty grid"),
+            GridError::Ragged {
+                row,
+                width,
+                expected,
+            } => write!(f, "row {} is {} wide, expected {}", row, width, expected),
+        }
+    }
+}
+
+impl Error for GridError {}
+
+impl GridError {
+    /// The error as a `ParseError` pointing at the line of `raw` it's about.
+    pub fn in_input(self, raw: &str) -> ParseError {
+        let line = match self {
+            GridError::Empty => 1,
+            GridError::Ragged { row, .. } => row,
+        };
+        ParseError::new(line, raw.lines().nth(line - 1).unwrap_or_default(), self)
+    }
+}
+
+/// A dense, rectangular grid, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Build a grid from its rows, which all have to be the same width.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Grid<T>, GridError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for row in rows {
+            let expected = *width.get_or_insert(row.len());
+            if row.len() != expected {
+                return Err(GridError::Ragged {
+                    row: height + 1,
+                    width: row.len(),
+                    expected,
+                });
+            }
+            cells.extend(row);
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(GridError::Empty),
+        }
+    }
+
+    /// Parse a character map, one row per line, turning each character into a cell.
+    pub fn parse(raw: &str, mut cell: impl FnMut(char) -> T) -> Result<Grid<T>, GridError> {
+        Grid::from_rows(
+            raw.lines()
+                .map(|line| line.chars().map(&mut cell).collect()),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Vec2) -> bool {
+        self.index_of(pos).is_some()
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// The orthogonal neighbours of `pos` that are on the grid.
+    pub fn neighbours4(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        neighbours4(pos).filter(|n| self.contains(*n))
+    }
+
+    /// The orthogonal and diagonal neighbours of `pos` that are on the grid.
+    pub fn neighbours8(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        neighbours8(pos).filter(|n| self.contains(*n))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Vec2> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Vec2::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Where the first cell matching `predicate` is, row by row.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Vec2> {
+        self.iter()
+            .find(|(_pos, cell)| predicate(cell))
+            .map(|(pos, _cell)| pos)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is outside the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn index_of(&self, pos: Vec2) -> Option<usize> {
+        let (x, y) = (pos.x, pos.y);
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(x as usize + y as usize * self.width)
+    }
+}
+
+impl<T> Index<Vec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Vec2) -> &T {
+        match self.index_of(pos) {
+            Some(i) => &self.cells[i],
+            None => panic!(
+                "{:?} is outside the {}x{} grid",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Vec2> for Grid<T> {
+    fn index_mut(&mut self, pos: Vec2) -> &mut T {
+        match self.index_of(pos) {
+            Some(i) => &mut self.cells[i],
+            None => panic!(
+                "{:?} is outside the {}x{} grid",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+// draws the grid the way the puzzles do, one line per row
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A grid with no fixed size, holding only the cells that have something in them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Vec2, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    /// Parse a character map, keeping the cells that `cell` turns into something.
+    pub fn parse(raw: &str, mut cell: impl FnMut(char) -> Option<T>) -> SparseGrid<T> {
+        let mut grid = SparseGrid::new();
+        for (y, line) in raw.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if let Some(value) = cell(c) {
+                    grid.insert(Vec2::new(x as i32, y as i32), value);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: Vec2) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn insert(&mut self, pos: Vec2, value: T) -> Option<T> {
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: Vec2) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    /// The orthogonal neighbours of `pos` that have something in them.
+    pub fn neighbours4(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        neighbours4(pos).filter(|n| self.contains(*n))
+    }
+
+    /// The orthogonal and diagonal neighbours of `pos` that have something in them.
+    pub fn neighbours8(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        neighbours8(pos).filter(|n| self.contains(*n))
+    }
+
+    /// Occupied positions, in no particular order.
+    pub fn positions(&self) -> impl Iterator<Item = Vec2> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        self.cells.iter().map(|(pos, value)| (*pos, value))
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// The part inside `bounds` as a dense grid, with `empty` wherever there's nothing.
+    /// The grid starts from the top left corner of `bounds`, not from the origin.
+    pub fn to_grid(&self, bounds: Aabb<2>, empty: T) -> Grid<T> {
+        let [width, height] = bounds.size();
+        let origin = Vec2::new(bounds.min[0], bounds.min[1]);
+        let mut grid = Grid::new(width as usize, height as usize, empty);
+        for (pos, value) in self.iter() {
+            if let Some(cell) = grid.get_mut(pos - origin) {
+                *cell = value.clone();
+            }
+        }
+        grid
+    }
+}
+
+impl<T> FromIterator<(Vec2, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Vec2, T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<(Vec2, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Vec2, T)>>(&mut self, iter: I) {
+        self.cells.extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits() -> Grid<char> {
+        Grid::parse("123\n456", |c| c).unwrap()
+    }
+
+    #[test]
+    fn parse_rejects_ragged_rows() {
+        let err = Grid::parse("12\n345", |c| c).unwrap_err();
+        assert_eq!(
+            err,
+            GridError::Ragged {
+                row: 2,
+                width: 3,
+                expected: 2
+            }
+        );
+        assert_eq!(Grid::parse("", |c| c).unwrap_err(), GridError::Empty);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = digits();
+        let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+        assert_eq!(rows, ["123", "456"]);
+        let columns: Vec<String> = grid.columns().map(|column| column.collect()).collect();
+        assert_eq!(columns, ["14", "25", "36"]);
+        assert_eq!(grid.column(2).collect::<String>(), "36");
+    }
+
+    #[test]
+    fn neighbours_stay_on_the_grid() {
+        let grid = digits();
+        let corner: Vec<char> = grid.neighbours4(Vec2::ZERO).map(|pos| grid[pos]).collect();
+        assert_eq!(corner, ['2', '4']);
+        let middle: Vec<char> = grid
+            .neighbours8(Vec2::new(1, 0))
+            .map(|pos| grid[pos])
+            .collect();
+        assert_eq!(middle, ['1', '3', '4', '5', '6']);
+        assert_eq!(grid.get(Vec2::new(3, 0)), None);
+        assert_eq!(grid.get(Vec2::new(0, -1)), None);
+    }
+
+    #[test]
+    fn sparse_grid() {
+        let mut grid = SparseGrid::parse(".#.\n##.", |c| (c == '#').then_some(c));
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.get(Vec2::new(1, 0)), Some(&'#'));
+        assert_eq!(grid.get(Vec2::ZERO), None);
+
+        let mut around: Vec<Vec2> = grid.neighbours4(Vec2::new(1, 1)).collect();
+        around.sort();
+        assert_eq!(around, [Vec2::new(0, 1), Vec2::new(1, 0)]);
+
+        *grid.get_mut(Vec2::new(0, 1)).unwrap() = 'o';
+        assert_eq!(grid.remove(Vec2::new(0, 1)), Some('o'));
+        assert_eq!(grid.remove(Vec2::new(1, 0)), Some('#'));
+        assert_eq!(grid.remove(Vec2::new(1, 1)), Some('#'));
+        assert!(grid.is_empty());
+    }
+}
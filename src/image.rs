@@ -0,0 +1,262 @@
+use std::{collections::HashMap, fmt::Write as _, fs, io, path::Path};
+
+use clap::ValueEnum;
+
+use crate::{geometry::Vec2, grid::Grid};
+
+pub type Rgb = [u8; 3];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+/// The colour each character of a drawing is painted in.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    colours: HashMap<char, Rgb>,
+    other: Rgb,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        let mut colours = HashMap::from([
+            ('.', [240, 240, 240]),
+            ('#', [60, 60, 60]),
+            ('o', [230, 190, 90]),
+            ('S', [40, 90, 220]),
+            ('E', [220, 40, 40]),
+            ('H', [220, 40, 40]),
+            ('T', [40, 90, 220]),
+            ('^', [250, 180, 0]),
+            ('>', [250, 180, 0]),
+            ('v', [250, 180, 0]),
+            ('<', [250, 180, 0]),
+            ('|', [120, 120, 120]),
+            ('-', [120, 120, 120]),
+            ('+', [120, 120, 120]),
+        ]);
+        // rope knots, and blizzards piled on top of each other
+        for c in '1'..='9' {
+            colours.insert(c, [120, 120, 220]);
+        }
+        Palette {
+            colours,
+            other: [255, 0, 255],
+        }
+    }
+}
+
+impl Palette {
+    /// The default palette with some colours changed, given as `c=rrggbb` pairs separated
+    /// by commas, e.g. `#=000000,.=ffffff`.
+    pub fn parse(spec: &str) -> Result<Palette, String> {
+        let mut palette = Palette::default();
+        for entry in spec.split(',').filter(|entry| !entry.is_empty()) {
+            let mut chars = entry.chars();
+            let (Some(c), Some('=')) = (chars.next(), chars.next()) else {
+                return Err(format!("expected c=rrggbb, got {:?}", entry));
+            };
+            palette.colours.insert(c, hex_colour(chars.as_str())?);
+        }
+        Ok(palette)
+    }
+
+    pub fn colour(&self, c: char) -> Rgb {
+        self.colours.get(&c).copied().unwrap_or(self.other)
+    }
+}
+
+fn hex_colour(hex: &str) -> Result<Rgb, String> {
+    let hex = hex.trim_start_matches('#');
+    let value = match hex.len() {
+        6 => u32::from_str_radix(hex, 16).ok(),
+        _ => None,
+    };
+    match value {
+        Some(value) => Ok([(value >> 16) as u8, (value >> 8) as u8, value as u8]),
+        None => Err(format!("{:?} isn't a colour, expected rrggbb", hex)),
+    }
+}
+
+/// Save a drawing as an image, `scale` pixels square for each cell. Where there's a
+/// `backdrop`, the empty cells, drawn as `.`, are painted its colour instead.
+pub fn save(
+    picture: &Grid<char>,
+    backdrop: Option<&Grid<Rgb>>,
+    path: &Path,
+    format: ImageFormat,
+    palette: &Palette,
+    scale: usize,
+) -> io::Result<()> {
+    let colours = paint(picture, backdrop, palette);
+    match format {
+        ImageFormat::Ppm => fs::write(path, Image::render(&colours, scale).ppm()),
+        ImageFormat::Png => fs::write(path, Image::render(&colours, scale).png()),
+        ImageFormat::Svg => fs::write(path, svg(&colours, scale)),
+    }
+}
+
+fn paint(picture: &Grid<char>, backdrop: Option<&Grid<Rgb>>, palette: &Palette) -> Grid<Rgb> {
+    let mut colours = picture.map(|&c| palette.colour(c));
+    if let Some(backdrop) = backdrop {
+        for (pos, &c) in picture.iter() {
+            if let ('.', Some(&colour)) = (c, backdrop.get(pos)) {
+                colours[pos] = colour;
+            }
+        }
+    }
+    colours
+}
+
+/// A picture, `scale` pixels square for every cell of the grid of colours it was drawn
+/// from.
+#[derive(Debug, Clone)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn render(grid: &Grid<Rgb>, scale: usize) -> Image {
+        let (width, height) = (grid.width() * scale, grid.height() * scale);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let cell = Vec2::new((x / scale) as i32, (y / scale) as i32);
+                pixels.push(grid[cell]);
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Binary PPM, which needs nothing more than a header in front of the pixels.
+    pub fn ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.pixels.iter().flatten());
+        bytes
+    }
+
+    /// PNG, with the pixels stored rather than compressed so that it needs no more than
+    /// the checksums. The files are bigger for it, but every viewer opens them.
+    pub fn png(&self) -> Vec<u8> {
+        // each row starts with its filter type, and 0 is none
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, then default compression, filtering and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut bytes = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        png_chunk(&mut bytes, b"IHDR", &header);
+        png_chunk(&mut bytes, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+}
+
+fn png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend((data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+// a zlib stream of uncompressed deflate blocks, which hold at most 65535 bytes each
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        stream.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        stream.push(last as u8);
+        stream.extend((block.len() as u16).to_le_bytes());
+        stream.extend((!(block.len() as u16)).to_le_bytes());
+        stream.extend(block);
+    }
+    stream.extend(adler32(data).to_be_bytes());
+    stream
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// SVG, with a rectangle for each run of the same colour along a row.
+pub fn svg(picture: &Grid<Rgb>, scale: usize) -> String {
+    let (width, height) = (picture.width() * scale, picture.height() * scale);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+        width, height, width, height
+    );
+
+    for (y, row) in picture.rows().enumerate() {
+        let mut x = 0;
+        for run in row.chunk_by(|a, b| a == b) {
+            let [r, g, b] = run[0];
+            writeln!(
+                svg,
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{:02x}{:02x}{:02x}\"/>",
+                x * scale,
+                y * scale,
+                run.len() * scale,
+                scale,
+                r,
+                g,
+                b
+            )
+            .unwrap();
+            x += run.len();
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
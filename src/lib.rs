@@ -0,0 +1,8 @@
+//! The parts every day shares: points and boxes, grids, input parsing and searches. They
+//! live in a library of their own so that they're a public API, whether or not a day uses
+//! every part of it yet.
+
+pub mod geometry;
+pub mod grid;
+pub mod parser;
+pub mod search;
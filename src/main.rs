@@ -1,7 +1,34 @@
-use clap::Parser;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Read},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
+
+use aoc2022::{geometry, grid, parser, search};
+use clap::{Args, Parser, Subcommand};
+use solution::{Solution, Solved};
 use strum::IntoEnumIterator;
 use strum_macros::{EnumIter, EnumString};
 
+mod answers;
+mod draw;
+mod image;
+mod output;
+mod scaffold;
+mod solution;
+mod stepper;
+mod timing;
+
+// every day has its own module, which build.rs registers with `days!` below
 mod day01;
 mod day02;
 mod day03;
@@ -25,95 +52,410 @@ mod day22;
 mod day23;
 mod day24;
 mod day25;
-// NEXTMOD
-mod parser;
-
-#[derive(Debug, Clone, PartialEq, EnumString, EnumIter)]
-enum Days {
-    All,
-    Day01,
-    Day02,
-    Day03,
-    Day04,
-    Day05,
-    Day06,
-    Day09,
-    Day10,
-    Day11,
-    Day12,
-    Day13,
-    Day14,
-    Day15,
-    Day16,
-    Day17,
-    Day18,
-    Day19,
-    Day20,
-    Day21,
-    Day22,
-    Day23,
-    Day24,
-    Day25,
-    // NEXTENUM
+
+// Declares the `Days` enum and dispatches to each day's `Solution`. build.rs calls this
+// with every `src/dayNN.rs` it finds, once it's checked each one has a `mod` above.
+macro_rules! days {
+    ($($day:ident => $solution:ty),* $(,)?) => {
+        #[derive(Debug, Clone, PartialEq, EnumString, EnumIter)]
+        enum Days {
+            All,
+            $($day,)*
+        }
+
+        impl Days {
+            fn run_one(&self, selection: &Selection, report: &mut dyn FnMut(Solved)) {
+                match self {
+                    $(Days::$day => run::<$solution>(self, selection, report),)*
+                    Days::All => Days::run_all(selection, 1, report),
+                }
+            }
+
+            fn draw_one(&self, selection: &Selection, options: &draw::DrawOptions) -> Result<(), String> {
+                match self {
+                    $(Days::$day => draw::draw::<$solution>(self, selection, options),)*
+                    Days::All => Err(String::from("draw needs a single day, not All")),
+                }
+            }
+
+            fn explain_one(&self, selection: &Selection) -> Result<(), String> {
+                match self {
+                    $(Days::$day => explain::<$solution>(self, selection),)*
+                    Days::All => Err(String::from("explain needs a single day, not All")),
+                }
+            }
+
+            fn step_one(&self, selection: &Selection) -> Result<(), String> {
+                match self {
+                    $(Days::$day => stepper::step::<$solution>(self, selection),)*
+                    Days::All => Err(String::from("step needs a single day, not All")),
+                }
+            }
+
+            // a stub is a day whose part 1 still answers `Pending`
+            fn is_stub(&self) -> bool {
+                match self {
+                    $(Days::$day => solution::is_pending::<$solution>(),)*
+                    Days::All => false,
+                }
+            }
+        }
+    };
 }
 
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
 #[derive(Parser)]
 struct Cli {
-    day: Days,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a day, or All of them, and print the answers
+    Run {
+        day: Days,
+        #[command(flatten)]
+        selection: Selection,
+        /// Time parsing and each part, and print a summary table at the end
+        #[arg(long)]
+        time: bool,
+        /// How to print the answers
+        #[arg(long, value_enum, default_value_t = output::Format::Text)]
+        format: output::Format,
+        /// Run this many days at once when running All
+        #[arg(long, short, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: u16,
+    },
+    /// Check answers against the recorded answers file
+    Verify {
+        #[arg(default_value = "All")]
+        day: Days,
+        #[command(flatten)]
+        selection: Selection,
+        /// Run this many days at once when running All
+        #[arg(long, short, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: u16,
+    },
+    /// Print a picture of the puzzle, for the days that can draw one
+    Draw {
+        day: Days,
+        #[command(flatten)]
+        selection: Selection,
+        #[command(flatten)]
+        options: draw::DrawOptions,
+    },
+    /// Show how the answers were reached, for the days that can
+    Explain {
+        day: Days,
+        #[command(flatten)]
+        selection: Selection,
+    },
+    /// Step back and forth through a day's simulation in the terminal, for the days that
+    /// can animate one
+    Step {
+        day: Days,
+        #[command(flatten)]
+        selection: Selection,
+    },
+    /// List the registered days, marking the ones that are still stubs
+    List,
+    /// Set up a new day from the template, with empty data files
+    Scaffold {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+}
+
+/// Which parts and inputs to run; by default both parts against every input the day lists.
+#[derive(Args)]
+struct Selection {
+    /// Only run this part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Only run the example inputs
+    #[arg(long, conflicts_with_all = ["real", "input"])]
+    example: bool,
+    /// Only run the real puzzle input
+    #[arg(long, conflicts_with = "input")]
+    real: bool,
+    /// Run against this file instead of the day's data files, `-` for stdin
+    #[arg(long)]
+    input: Option<String>,
+}
+
+impl Selection {
+    // (label, path) for each input to run. Data files are labelled as in the answers file.
+    fn inputs(&self, day: &Days, extensions: &[&str]) -> Vec<(String, String)> {
+        if let Some(path) = &self.input {
+            let label = match path.as_str() {
+                "-" => String::from("stdin"),
+                _ => Path::new(path)
+                    .file_name()
+                    .map_or(path.clone(), |name| name.to_string_lossy().into_owned()),
+            };
+            return vec![(label, path.clone())];
+        }
+
+        extensions
+            .iter()
+            .filter(|extension| match **extension {
+                "txt" => !self.example,
+                _ => !self.real,
+            })
+            .map(|extension| {
+                let label = format!("{}.{}", day.name(), extension);
+                let path = format!("data/{}", label);
+                (label, path)
+            })
+            .collect()
+    }
 }
 
 impl Days {
-    fn run_one(&self) {
-        println!("running {:?}", self);
+    fn run_all(selection: &Selection, jobs: u16, report: &mut dyn FnMut(Solved)) {
+        let days: Vec<Days> = Days::iter().filter(|day| *day != Days::All).collect();
+        if jobs <= 1 {
+            for day in &days {
+                day.run_one(selection, report);
+            }
+            return;
+        }
+
+        // workers pull the next day off a shared counter, and results are held back
+        // until every earlier day has been reported so the output stays in day order.
+        let next_day = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+        thread::scope(|scope| {
+            for _ in 0..(jobs as usize).min(days.len()) {
+                let sender = sender.clone();
+                let (days, next_day) = (&days, &next_day);
+                scope.spawn(move || loop {
+                    let index = next_day.fetch_add(1, Ordering::Relaxed);
+                    let Some(day) = days.get(index) else {
+                        break;
+                    };
+                    sender.send((index, day.run_isolated(selection))).unwrap();
+                });
+            }
+            drop(sender);
+
+            let mut finished = BTreeMap::new();
+            let mut next_report = 0;
+            for (index, solved) in receiver {
+                finished.insert(index, solved);
+                while let Some(solved) = finished.remove(&next_report) {
+                    solved.into_iter().for_each(&mut *report);
+                    next_report += 1;
+                }
+            }
+        });
+    }
+
+    // run on a worker thread: collect the results, and turn a panic that escapes the
+    // day into an error rather than losing the whole run
+    fn run_isolated(&self, selection: &Selection) -> Vec<Solved> {
+        let mut solved = Vec::new();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.run_one(selection, &mut |s| solved.push(s))
+        }));
+        if let Err(payload) = result {
+            let message = solution::panic_message(&*payload);
+            solved.push(Solved {
+                day: format!("{:?}", self),
+                input: self.name(),
+                parse_time: Duration::ZERO,
+                answers: solution::failed(selection.part, &message),
+            });
+        }
+        solved
+    }
+
+    fn name(&self) -> String {
+        format!("{:?}", self).to_lowercase()
+    }
+
+    fn run(&self, selection: &Selection, jobs: u16, report: &mut dyn FnMut(Solved)) {
         match self {
-            Days::Day01 => day01::solve(),
-            Days::Day02 => day02::solve(),
-            Days::Day03 => day03::solve(),
-            Days::Day04 => day04::solve(),
-            Days::Day05 => day05::solve(),
-            Days::Day06 => day06::solve(),
-            Days::Day09 => day09::solve(),
-            Days::Day10 => day10::solve(),
-            Days::Day11 => day11::solve(),
-            Days::Day12 => day12::solve(),
-            Days::Day13 => day13::solve(),
-            Days::Day14 => day14::solve(),
-            Days::Day15 => day15::solve(),
-            Days::Day16 => day16::solve(),
-            Days::Day17 => day17::solve(),
-            Days::Day18 => day18::solve(),
-            Days::Day19 => day19::solve(),
-            Days::Day20 => day20::solve(),
-            Days::Day21 => day21::solve(),
-            Days::Day22 => day22::solve(),
-            Days::Day23 => day23::solve(),
-            Days::Day24 => day24::solve(),
-            Days::Day25 => day25::solve(),
-            // NEXTMATCH
-            _ => panic!("Requested day [{:?}] not implemented!", self),
+            Days::All => Days::run_all(selection, jobs, report),
+            _ => self.run_one(selection, report),
         }
     }
+}
+
+fn run<S: Solution>(day: &Days, selection: &Selection, report: &mut dyn FnMut(Solved)) {
+    for (input, filename) in selection.inputs(day, S::INPUTS) {
+        let (parse_time, answers) = match read_input(&filename) {
+            Ok(raw) => solution::solve::<S>(&input, &raw, input_path(&filename), selection.part),
+            Err(err) => (
+                Duration::ZERO,
+                solution::failed(
+                    selection.part,
+                    &format!("couldn't read {}: {}", filename, err),
+                ),
+            ),
+        };
+        report(Solved {
+            day: format!("{:?}", day),
+            input,
+            parse_time,
+            answers,
+        });
+    }
+}
+
+// how to refer to an input in messages
+fn input_path(filename: &str) -> &str {
+    if filename == "-" {
+        "<stdin>"
+    } else {
+        filename
+    }
+}
+
+fn read_input(filename: &str) -> io::Result<String> {
+    if filename == "-" {
+        let mut raw = String::new();
+        io::stdin().read_to_string(&mut raw)?;
+        return Ok(raw);
+    }
+    fs::read_to_string(filename)
+}
 
-    fn run_all() {
-        println!("running all");
-        for day in Days::iter() {
-            match day {
-                Days::All => continue,
-                _ => (),
+// print how each selected input and part was answered
+fn explain<S: Solution>(day: &Days, selection: &Selection) -> Result<(), String> {
+    for (input, filename) in selection.inputs(day, S::INPUTS) {
+        let raw =
+            read_input(&filename).map_err(|err| format!("couldn't read {}: {}", filename, err))?;
+        let parsed = S::parse_input(&input, &raw)
+            .map_err(|err| err.in_file(input_path(&filename)).to_string())?;
+
+        for part in [1, 2] {
+            if selection.part.is_some_and(|selected| selected != part) {
+                continue;
             }
-            day.run_one();
+            let Some(explanation) = S::explain(&parsed, part) else {
+                return Err(format!("{:?} has nothing to explain", day));
+            };
+            println!("{} part {}:", input, part);
+            print!("{}", explanation);
         }
     }
+    Ok(())
+}
 
-    fn run(&self) {
-        match self {
-            Days::All => Days::run_all(),
-            _ => self.run_one(),
+fn verify(day: &Days, selection: &Selection, jobs: u16) -> ExitCode {
+    let known = match answers::load(answers::ANSWERS_FILE) {
+        Ok(known) => known,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+
+    day.run(selection, jobs, &mut |solved| {
+        for answer in &solved.answers {
+            match answers::check(&known, &solved.input, answer) {
+                answers::Verdict::Pass(value) => {
+                    passed += 1;
+                    println!("PASS {} part {}: {}", solved.input, answer.part, value);
+                }
+                answers::Verdict::Fail(value, expected) => {
+                    failed += 1;
+                    println!(
+                        "FAIL {} part {}: got {}, expected {}",
+                        solved.input, answer.part, value, expected
+                    );
+                }
+                answers::Verdict::Error(err) => {
+                    failed += 1;
+                    println!("FAIL {} part {}: error: {}", solved.input, answer.part, err);
+                }
+                answers::Verdict::Unknown(value) => {
+                    unknown += 1;
+                    println!("???? {} part {}: {}", solved.input, answer.part, value);
+                }
+            }
         }
+    });
+
+    println!("{} passed, {} failed, {} unknown", passed, failed, unknown);
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
     }
 }
 
-fn main() {
+fn main() -> ExitCode {
     let args = Cli::parse();
-    args.day.run();
+    match args.command {
+        Command::Run { day, selection, .. } | Command::Verify { day, selection, .. }
+            if day == Days::All && selection.input.is_some() =>
+        {
+            eprintln!("--input needs a single day, not All");
+            ExitCode::FAILURE
+        }
+        Command::Run {
+            day,
+            selection,
+            time,
+            format,
+            jobs,
+        } => {
+            let mut output = output::Output::new(format, time);
+            day.run(&selection, jobs, &mut |solved| output.report(&solved));
+            output.finish();
+            ExitCode::SUCCESS
+        }
+        Command::Verify {
+            day,
+            selection,
+            jobs,
+        } => verify(&day, &selection, jobs),
+        Command::Draw {
+            day,
+            selection,
+            options,
+        } => match day.draw_one(&selection, &options) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("{}", err);
+                ExitCode::FAILURE
+            }
+        },
+        Command::Explain { day, selection } => match day.explain_one(&selection) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("{}", err);
+                ExitCode::FAILURE
+            }
+        },
+        Command::Step { day, selection } => match day.step_one(&selection) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("{}", err);
+                ExitCode::FAILURE
+            }
+        },
+        Command::List => {
+            for day in Days::iter().filter(|day| *day != Days::All) {
+                if day.is_stub() {
+                    println!("{:?} (stub)", day);
+                } else {
+                    println!("{:?}", day);
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Scaffold { day } => match scaffold::scaffold(day) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("{}", err);
+                ExitCode::FAILURE
+            }
+        },
+    }
 }
@@ -0,0 +1,139 @@
+use clap::ValueEnum;
+
+use crate::{solution::Solved, timing::Timings};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+/// Everything `run` prints goes through here: one record per day, part and input.
+pub struct Output {
+    format: Format,
+    time: bool,
+    timings: Timings,
+    day: Option<String>,
+    records: usize,
+}
+
+impl Output {
+    pub fn new(format: Format, time: bool) -> Output {
+        match format {
+            Format::Text => (),
+            Format::Json => println!("["),
+            Format::Csv => println!("day,input,part,status,answer,parse_ms,elapsed_ms"),
+        }
+        Output {
+            format,
+            time,
+            timings: Timings::default(),
+            day: None,
+            records: 0,
+        }
+    }
+
+    pub fn report(&mut self, solved: &Solved) {
+        if self.format == Format::Text && self.day.as_ref() != Some(&solved.day) {
+            println!("running {}", solved.day);
+        }
+        self.day = Some(solved.day.clone());
+        self.timings.add(solved);
+
+        for answer in &solved.answers {
+            let (status, value) = match &answer.value {
+                Ok(value) => ("ok", value),
+                Err(err) => ("error", err),
+            };
+            let parse_ms = solved.parse_time.as_secs_f64() * 1000.0;
+            let elapsed_ms = answer.elapsed.as_secs_f64() * 1000.0;
+
+            match self.format {
+                Format::Text => {
+                    let value = match &answer.value {
+                        Ok(value) => value.clone(),
+                        Err(err) => format!("error: {}", err),
+                    };
+                    if self.time {
+                        println!(
+                            "{} part {}: {} ({:.2?})",
+                            solved.input, answer.part, value, answer.elapsed
+                        );
+                    } else {
+                        println!("{} part {}: {}", solved.input, answer.part, value);
+                    }
+                }
+                Format::Json => {
+                    if self.records > 0 {
+                        println!(",");
+                    }
+                    print!(
+                        "  {{\"day\": {}, \"input\": {}, \"part\": {}, \"status\": {}, \"{}\": {}, \"parse_ms\": {:.3}, \"elapsed_ms\": {:.3}}}",
+                        json_string(&solved.day),
+                        json_string(&solved.input),
+                        answer.part,
+                        json_string(status),
+                        if answer.value.is_ok() { "answer" } else { "error" },
+                        json_string(value),
+                        parse_ms,
+                        elapsed_ms,
+                    );
+                }
+                Format::Csv => println!(
+                    "{},{},{},{},{},{:.3},{:.3}",
+                    csv_field(&solved.day),
+                    csv_field(&solved.input),
+                    answer.part,
+                    status,
+                    csv_field(value),
+                    parse_ms,
+                    elapsed_ms,
+                ),
+            }
+            self.records += 1;
+        }
+    }
+
+    pub fn finish(self) {
+        match self.format {
+            Format::Text => {
+                if self.time {
+                    self.timings.print();
+                }
+            }
+            Format::Json => {
+                if self.records > 0 {
+                    println!();
+                }
+                println!("]");
+            }
+            Format::Csv => (),
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        String::from(value)
+    }
+}
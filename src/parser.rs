@@ -1,21 +1,184 @@
-use std::{fmt::Debug, fs, str::FromStr};
+use std::{error::Error, fmt, num::ParseIntError, str::FromStr};
 
-pub fn records_from_lines<T>(filename: &str) -> Vec<T>
+/// A line of input that didn't parse, with enough context to find it.
+#[derive(Debug)]
+pub struct ParseError {
+    /// Filled in by the runner, which is the one that knows where the input came from.
+    pub path: Option<String>,
+    /// 1-based, like an editor.
+    pub line: usize,
+    pub text: String,
+    pub source: Box<dyn Error + Send + Sync>,
+}
+
+impl ParseError {
+    pub fn new(line: usize, text: &str, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        ParseError {
+            path: None,
+            line,
+            text: String::from(text),
+            source: source.into(),
+        }
+    }
+
+    pub fn in_file(self, path: &str) -> Self {
+        ParseError {
+            path: Some(String::from(path)),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: couldn't parse {:?}: {}",
+            self.path.as_deref().unwrap_or("<input>"),
+            self.line,
+            self.text,
+            self.source
+        )
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+/// What's wrong with a single record, for `FromStr` impls that don't have an error type
+/// of their own to return.
+#[derive(Debug)]
+pub struct RecordError(pub String);
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for RecordError {}
+
+impl From<ParseIntError> for RecordError {
+    fn from(err: ParseIntError) -> Self {
+        RecordError(err.to_string())
+    }
+}
+
+/// The `index`th space-separated field of `s`, or an error naming the missing `field`.
+pub fn field<'a>(s: &'a str, index: usize, field: &str) -> Result<&'a str, RecordError> {
+    s.split_whitespace()
+        .nth(index)
+        .ok_or_else(|| RecordError(format!("missing {}", field)))
+}
+
+pub fn records_from_lines<T>(raw: &str) -> Result<Vec<T>, ParseError>
 where
     T: FromStr,
-    T::Err: Debug,
+    T::Err: Error + Send + Sync + 'static,
+{
+    lines_from(1, raw)
+}
+
+/// A run of non-blank lines, as found between blank lines in the input.
+#[derive(Debug)]
+pub struct Block {
+    /// 1-based line number of the block's first line in the whole input.
+    pub line: usize,
+    pub text: String,
+}
+
+impl Block {
+    /// Parse the whole block as one record.
+    pub fn parse<T>(&self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Error + Send + Sync + 'static,
+    {
+        self.parse_with(str::parse)
+    }
+
+    /// Parse the whole block with `f`, for records that aren't worth a `FromStr` impl.
+    pub fn parse_with<T, E>(&self, f: impl FnOnce(&str) -> Result<T, E>) -> Result<T, ParseError>
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        // the first line is enough to find the block, the whole thing would be noise
+        let first = self.text.lines().next().unwrap_or_default();
+        f(&self.text).map_err(|err| ParseError::new(self.line, first, err))
+    }
+
+    /// Parse each line of the block as a record, numbering lines from the whole input.
+    pub fn records<T>(&self) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Error + Send + Sync + 'static,
+    {
+        lines_from(self.line, &self.text)
+    }
+}
+
+/// Split `raw` on blank lines. Runs of several blank lines don't make empty blocks.
+pub fn blocks(raw: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Block> = None;
+
+    for (i, line) in raw.lines().enumerate() {
+        if line.trim().is_empty() {
+            blocks.extend(current.take());
+            continue;
+        }
+        match &mut current {
+            Some(block) => {
+                block.text.push('\n');
+                block.text.push_str(line);
+            }
+            None => {
+                current = Some(Block {
+                    line: i + 1,
+                    text: String::from(line),
+                })
+            }
+        }
+    }
+    blocks.extend(current);
+
+    blocks
+}
+
+/// Each blank-line-separated group of `raw`, with every line parsed as a record.
+pub fn groups_from_lines<T>(raw: &str) -> Result<Vec<Vec<T>>, ParseError>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    blocks(raw).iter().map(Block::records).collect()
+}
+
+/// Each blank-line-separated block of `raw` parsed as one record.
+pub fn records_from_blocks<T>(raw: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    blocks(raw).iter().map(Block::parse).collect()
+}
+
+fn lines_from<T>(first_line: usize, raw: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
 {
-    let raw = fs::read_to_string(filename).unwrap_or_else(|_err| {
-        panic!(
-            "we're hardcoding all filenames, but didn't find this one {}",
-            filename
-        )
-    });
     let mut data: Vec<T> = Vec::new();
 
-    for r in raw.lines() {
-        data.push(r.parse().expect("failed to parse input file"));
+    for (i, r) in raw.lines().enumerate() {
+        data.push(
+            r.parse()
+                .map_err(|err| ParseError::new(first_line + i, r, err))?,
+        );
     }
 
-    data
+    Ok(data)
 }
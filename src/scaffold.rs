@@ -0,0 +1,125 @@
+use std::{env, fs, path::Path, process::Command};
+
+const TEMPLATE: &str = "use crate::{
+    parser::ParseError,
+    solution::{Pending, Solution},
+};
+
+pub struct DayNN;
+
+impl Solution for DayNN {
+    type Input = ();
+    type Answer1 = Pending;
+    type Answer2 = Pending;
+
+    fn parse(_raw: &str) -> Result<(), ParseError> {
+        Ok(())
+    }
+
+    fn part1(_input: &()) -> Pending {
+        Pending
+    }
+
+    fn part2(_input: &()) -> Pending {
+        Pending
+    }
+}
+";
+
+/// Create `src/dayNN.rs` and its data files, and declare the day in main.rs. build.rs
+/// picks the new day up from there.
+///
+/// Data files that are already on disk are left alone, so an interrupted scaffold can be
+/// re-run, but a day whose source file exists is refused rather than touched.
+pub fn scaffold(day: u8) -> Result<(), String> {
+    let name = format!("day{:02}", day);
+    let source = format!("src/{}.rs", name);
+
+    if !Path::new("src").is_dir() {
+        return Err(String::from("couldn't find src/, run from the repo root"));
+    }
+    if Path::new(&source).exists() {
+        return Err(format!("{} already exists", name));
+    }
+
+    let example = format!("data/{}.example", name);
+    if Path::new(&example).exists() {
+        println!("{} exists, leaving it alone", example);
+    } else {
+        write_new(&example, "")?;
+    }
+
+    let input = format!("data/{}.txt", name);
+    if Path::new(&input).exists() {
+        println!("{} exists, leaving it alone", input);
+    } else {
+        write_new(&input, &download(day))?;
+    }
+
+    // the source file goes last: once it exists the day counts as scaffolded
+    let template = TEMPLATE.replace("DayNN", &format!("Day{:02}", day));
+    write_new(&source, &template)?;
+    declare(&name)
+}
+
+// add `mod dayNN;` to main.rs, in order among the other days
+fn declare(name: &str) -> Result<(), String> {
+    let main = "src/main.rs";
+    let text =
+        fs::read_to_string(main).map_err(|err| format!("couldn't read {}: {}", main, err))?;
+    let declaration = format!("mod {};", name);
+    let days: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .filter(|(_i, line)| line.starts_with("mod day") && line.ends_with(';'))
+        .collect();
+    let Some(&(first, _line)) = days.first() else {
+        return Err(format!(
+            "couldn't find the days in {}, add `{}` by hand",
+            main, declaration
+        ));
+    };
+    // after the last day that comes before this one, or before all of them
+    let at = days
+        .iter()
+        .filter(|(_i, line)| *line < declaration.as_str())
+        .map(|(i, _line)| i + 1)
+        .next_back()
+        .unwrap_or(first);
+
+    let mut lines: Vec<&str> = text.lines().collect();
+    lines.insert(at, &declaration);
+    fs::write(main, lines.join("\n") + "\n")
+        .map_err(|err| format!("couldn't write {}: {}", main, err))?;
+    println!("declared {} in {}", name, main);
+    Ok(())
+}
+
+fn write_new(filename: &str, contents: &str) -> Result<(), String> {
+    fs::write(filename, contents).map_err(|err| format!("couldn't write {}: {}", filename, err))?;
+    println!("created {}", filename);
+    Ok(())
+}
+
+// fetch the puzzle input with the session cookie in AOC_COOKIE, if there is one. Any
+// failure leaves an empty file to paste the input into by hand.
+fn download(day: u8) -> String {
+    let cookie = match env::var("AOC_COOKIE") {
+        Ok(cookie) => cookie,
+        Err(_) => return String::new(),
+    };
+    let url = format!("https://adventofcode.com/2022/day/{}/input", day);
+    let fetched = Command::new("curl")
+        .args(["--silent", "--fail", "--cookie"])
+        .arg(format!("session={}", cookie))
+        .arg(&url)
+        .output();
+
+    match fetched {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).into(),
+        _ => {
+            println!("couldn't download {}, leaving the input empty", url);
+            String::new()
+        }
+    }
+}
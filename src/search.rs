@@ -0,0 +1,189 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+// Searches work on any state that can be hashed: a grid position, a position plus the
+// time, a whole game state. `successors` says where a state can go next, and the search
+// never needs to know more about the problem than that.
+
+/// The cheapest route to a goal: its total cost and every state along it, from the
+/// start to the goal inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    pub nodes: Vec<N>,
+}
+
+impl<N, C> Path<N, C> {
+    pub fn start(&self) -> &N {
+        &self.nodes[0]
+    }
+
+    pub fn goal(&self) -> &N {
+        &self.nodes[self.nodes.len() - 1]
+    }
+}
+
+// every state reached so far, with the index of the one it was reached from
+struct Visited<N> {
+    nodes: Vec<(N, usize)>,
+}
+
+impl<N: Clone> Visited<N> {
+    fn new(start: N) -> Visited<N> {
+        Visited {
+            nodes: vec![(start, 0)],
+        }
+    }
+
+    fn push(&mut self, node: N, parent: usize) -> usize {
+        self.nodes.push((node, parent));
+        self.nodes.len() - 1
+    }
+
+    fn path<C>(&self, mut index: usize, cost: C) -> Path<N, C> {
+        let mut nodes = vec![self.nodes[index].0.clone()];
+        while index != 0 {
+            index = self.nodes[index].1;
+            nodes.push(self.nodes[index].0.clone());
+        }
+        nodes.reverse();
+        Path { cost, nodes }
+    }
+}
+
+/// Breadth-first search, for when every step costs the same. The cost is the number of
+/// steps taken.
+pub fn bfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = Visited::new(start.clone());
+    let mut seen = HashSet::from([start]);
+    let mut frontier = VecDeque::from([(0, 0)]);
+
+    while let Some((index, steps)) = frontier.pop_front() {
+        let node = visited.nodes[index].0.clone();
+        if is_goal(&node) {
+            return Some(visited.path(index, steps));
+        }
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                let next_index = visited.push(next, index);
+                frontier.push_back((next_index, steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Dijkstra's algorithm, for steps with different, non-negative costs.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_node| C::default(), is_goal)
+}
+
+/// A* search. `heuristic` estimates the cost left from a state to the nearest goal, and
+/// has to never overestimate it for the path found to be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut visited = Visited::new(start.clone());
+    // the cheapest known cost to each state, and where it's recorded in `visited`
+    let mut best = HashMap::from([(start.clone(), (C::default(), 0))]);
+    let mut frontier = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+
+    while let Some(Reverse((_estimate, cost, index))) = frontier.pop() {
+        let node = visited.nodes[index].0.clone();
+        // a cheaper way here turned up after this one was queued
+        if best[&node].1 != index {
+            continue;
+        }
+        if is_goal(&node) {
+            return Some(visited.path(index, cost));
+        }
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            if let Some(&(known, _index)) = best.get(&next) {
+                if known <= next_cost {
+                    continue;
+                }
+            }
+            let estimate = next_cost + heuristic(&next);
+            let next_index = visited.push(next.clone(), index);
+            best.insert(next, (next_cost, next_index));
+            frontier.push(Reverse((estimate, next_cost, next_index)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -1- b -1- c, with a costly shortcut a -5- c and a dead end at d
+    fn roads(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('c', 5), ('b', 1), ('d', 1)],
+            'b' => vec![('a', 1), ('c', 1)],
+            'c' => vec![('a', 5), ('b', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn bfs_takes_the_fewest_steps() {
+        let steps = |node: &char| roads(node).into_iter().map(|(next, _cost)| next);
+        let path = bfs('a', steps, |&node| node == 'c').unwrap();
+        assert_eq!(path.cost, 1);
+        assert_eq!(path.nodes, ['a', 'c']);
+        assert_eq!(bfs('d', steps, |&node| node == 'c'), None);
+    }
+
+    #[test]
+    fn dijkstra_takes_the_cheapest_route() {
+        let path = dijkstra('a', roads, |&node| node == 'c').unwrap();
+        assert_eq!(path.cost, 2);
+        assert_eq!(path.nodes, ['a', 'b', 'c']);
+        assert_eq!((*path.start(), *path.goal()), ('a', 'c'));
+        assert_eq!(dijkstra('d', roads, |&node| node == 'a'), None);
+    }
+
+    #[test]
+    fn astar_agrees_with_dijkstra() {
+        let heuristic = |node: &char| match node {
+            'a' => 2,
+            'b' => 1,
+            _ => 0,
+        };
+        let path = astar('a', roads, heuristic, |&node| node == 'c').unwrap();
+        assert_eq!(path, dijkstra('a', roads, |&node| node == 'c').unwrap());
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    any::{Any, TypeId},
+    fmt::{self, Display},
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::{draw::Frames, grid::Grid, image::Rgb, parser::ParseError};
+
+/// The shape every day takes: parse the raw input once, then answer each part from it.
+pub trait Solution {
+    type Input;
+    type Answer1: Display + 'static;
+    type Answer2: Display + 'static;
+
+    /// Input files to run against, as extensions of `data/dayNN`.
+    const INPUTS: &'static [&'static str] = &["example", "txt"];
+
+    fn parse(raw: &str) -> Result<Self::Input, ParseError>;
+
+    /// Parse `raw`, knowing which input it is, e.g. `day15.example`. Only the days whose
+    /// question changes from one input to another need more than `parse`.
+    fn parse_input(_input: &str, raw: &str) -> Result<Self::Input, ParseError> {
+        Self::parse(raw)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1;
+    fn part2(input: &Self::Input) -> Self::Answer2;
+
+    /// A picture of the puzzle after solving `part`, for the days that have one to show.
+    fn draw(_input: &Self::Input, _part: u8) -> Option<Grid<char>> {
+        None
+    }
+
+    /// A colour for each cell of the picture `draw` makes of `part`, showing through its
+    /// empty cells when it's saved as an image, for what the picture's characters can't.
+    fn backdrop(_input: &Self::Input, _part: u8) -> Option<Grid<Rgb>> {
+        None
+    }
+
+    /// Each step of the simulation behind `part`, for the days that are one.
+    fn animate(_input: &Self::Input, _part: u8, _frames: &mut Frames) {}
+
+    /// How the answer to `part` was reached, for the days where that's worth checking.
+    fn explain(_input: &Self::Input, _part: u8) -> Option<String> {
+        None
+    }
+}
+
+/// Answers for one input file, e.g. `day01.example`, along with how long parsing took.
+#[derive(Debug)]
+pub struct Solved {
+    pub day: String,
+    pub input: String,
+    pub parse_time: Duration,
+    pub answers: Vec<Answer>,
+}
+
+/// A part's answer, or the reason there isn't one.
+#[derive(Debug)]
+pub struct Answer {
+    pub part: u8,
+    pub value: Result<String, String>,
+    pub elapsed: Duration,
+}
+
+/// Answer for a part that hasn't been solved yet.
+#[derive(Debug)]
+pub struct Pending;
+
+impl Display for Pending {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Solution Pending...")
+    }
+}
+
+/// Whether `S` is still a stub, i.e. part 1 hasn't been written yet.
+pub fn is_pending<S: Solution>() -> bool {
+    TypeId::of::<S::Answer1>() == TypeId::of::<Pending>()
+}
+
+/// Parse `raw`, the input `input` read from `path`, and answer the requested part, or both
+/// when `part` is `None`. Returns the time spent parsing alongside the answers. A parse error
+/// or a panic while solving becomes an error answer rather than taking down the rest of the
+/// run.
+pub fn solve<S: Solution>(
+    input: &str,
+    raw: &str,
+    path: &str,
+    part: Option<u8>,
+) -> (Duration, Vec<Answer>) {
+    let start = Instant::now();
+    let input = match attempt(|| S::parse_input(input, raw)) {
+        Ok(Ok(input)) => input,
+        Ok(Err(err)) => {
+            return (
+                start.elapsed(),
+                failed(part, &err.in_file(path).to_string()),
+            )
+        }
+        Err(err) => return (start.elapsed(), failed(part, &err)),
+    };
+    let parse_time = start.elapsed();
+
+    let mut answers = Vec::new();
+    if part != Some(2) {
+        let start = Instant::now();
+        let value = attempt(|| S::part1(&input).to_string());
+        answers.push(Answer {
+            part: 1,
+            value,
+            elapsed: start.elapsed(),
+        });
+    }
+    if part != Some(1) {
+        let start = Instant::now();
+        let value = attempt(|| S::part2(&input).to_string());
+        answers.push(Answer {
+            part: 2,
+            value,
+            elapsed: start.elapsed(),
+        });
+    }
+    (parse_time, answers)
+}
+
+/// Error answers for the requested parts, when there's nothing to solve them from.
+pub fn failed(part: Option<u8>, error: &str) -> Vec<Answer> {
+    [1, 2]
+        .into_iter()
+        .filter(|p| part.is_none_or(|part| part == *p))
+        .map(|p| Answer {
+            part: p,
+            value: Err(String::from(error)),
+            elapsed: Duration::ZERO,
+        })
+        .collect()
+}
+
+fn attempt<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(&*payload))
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        String::from(*message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("panicked")
+    }
+}
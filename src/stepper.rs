@@ -0,0 +1,356 @@
+use std::{
+    fmt::Write as _,
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    process::Command,
+};
+
+use crate::{
+    draw::{Frame, Frames},
+    geometry::Vec2,
+    solution::Solution,
+    Days, Selection,
+};
+
+// how many frames are kept around the one on screen. The simulation is run again to
+// fetch any others, so that long runs don't have to fit in memory.
+const CACHED: usize = 256;
+
+const HELP: &str = "←/→ or h/l step  PgUp/PgDn 10 steps  Home/End  space play  +/- speed  \
+                    g jump  w/a/s/d pan  q quit";
+
+/// Step back and forth through the simulation behind the selected part, in the terminal.
+/// Only the first selected input is used, and part 1 unless another is selected.
+pub fn step<S: Solution>(day: &Days, selection: &Selection) -> Result<(), String> {
+    let Some((input, filename)) = selection.inputs(day, S::INPUTS).into_iter().next() else {
+        return Err(format!("{:?} has no inputs", day));
+    };
+    let raw = crate::read_input(&filename)
+        .map_err(|err| format!("couldn't read {}: {}", filename, err))?;
+    let parsed = S::parse_input(&input, &raw)
+        .map_err(|err| err.in_file(crate::input_path(&filename)).to_string())?;
+    let part = selection.part.unwrap_or(1);
+
+    let mut frames = FrameCache::<S>::new(&parsed, part);
+    if frames.total == 0 {
+        return Err(format!("{:?} has nothing to step through", day));
+    }
+
+    let mut terminal = Terminal::open()?;
+    let mut stepper = Stepper {
+        title: format!("{:?} {} part {}", day, input, part),
+        last: frames.total - 1,
+        step: 0,
+        playing: false,
+        speed: 1,
+        pan: None,
+        prompt: None,
+    };
+    stepper.run(&mut terminal, &mut frames);
+    Ok(())
+}
+
+// the frames for a stretch of steps, and what's needed to fetch more
+struct FrameCache<'a, S: Solution> {
+    input: &'a S::Input,
+    part: u8,
+    total: usize,
+    first: usize,
+    frames: Vec<Frame>,
+}
+
+impl<'a, S: Solution> FrameCache<'a, S> {
+    fn new(input: &'a S::Input, part: u8) -> FrameCache<'a, S> {
+        let mut counting = Frames::between(0..0, |_frame| {});
+        S::animate(input, part, &mut counting);
+        FrameCache {
+            input,
+            part,
+            total: counting.steps(),
+            first: 0,
+            frames: Vec::new(),
+        }
+    }
+
+    fn get(&mut self, step: usize) -> &Frame {
+        if !(self.first..self.first + self.frames.len()).contains(&step) {
+            // mostly ahead of `step`, since that's the usual way to go
+            self.first = step.saturating_sub(CACHED / 4);
+            let mut frames = Vec::with_capacity(CACHED);
+            S::animate(
+                self.input,
+                self.part,
+                &mut Frames::between(self.first..self.first + CACHED, |frame| frames.push(frame)),
+            );
+            self.frames = frames;
+        }
+        &self.frames[step - self.first]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Key {
+    Char(char),
+    Left,
+    Right,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Enter,
+    Backspace,
+    Escape,
+}
+
+// the terminal, switched into a mode where keys arrive as they're pressed, and put back
+// the way it was when this is dropped
+struct Terminal {
+    tty: File,
+    saved: String,
+}
+
+impl Terminal {
+    fn open() -> Result<Terminal, String> {
+        let tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .map_err(|err| format!("stepping needs a terminal: {}", err))?;
+        let saved = stty(&tty, &["-g"])?;
+        // reads give up after a tenth of a second, so playing can carry on without keys
+        stty(
+            &tty,
+            &["-icanon", "-echo", "-isig", "min", "0", "time", "1"],
+        )?;
+        let mut terminal = Terminal { tty, saved };
+        // the alternate screen, without a cursor
+        terminal.write("\x1b[?1049h\x1b[?25l");
+        Ok(terminal)
+    }
+
+    // (columns, rows), or a guess if stty can't say. A terminal that's never been given a
+    // size says it's 0 by 0, which is no more use than not knowing.
+    fn size(&self) -> (usize, usize) {
+        let size = stty(&self.tty, &["size"]).unwrap_or_default();
+        let (rows, columns) = size.split_once(' ').unwrap_or_default();
+        (
+            columns
+                .trim()
+                .parse()
+                .ok()
+                .filter(|&columns| columns > 0)
+                .unwrap_or(80),
+            rows.trim()
+                .parse()
+                .ok()
+                .filter(|&rows| rows > 0)
+                .unwrap_or(24),
+        )
+    }
+
+    fn write(&mut self, text: &str) {
+        // nothing sensible to do if the terminal has gone away
+        let _ = self.tty.write_all(text.as_bytes());
+        let _ = self.tty.flush();
+    }
+
+    // the key pressed, or none if nothing was pressed for a tenth of a second
+    fn key(&mut self) -> Option<Key> {
+        let mut buffer = [0; 8];
+        let read = self.tty.read(&mut buffer).unwrap_or(0);
+        let key = match &buffer[..read] {
+            [] => return None,
+            b"\x1b[D" | b"\x1bOD" => Key::Left,
+            b"\x1b[C" | b"\x1bOC" => Key::Right,
+            b"\x1b[A" | b"\x1bOA" => Key::Up,
+            b"\x1b[B" | b"\x1bOB" => Key::Down,
+            b"\x1b[5~" => Key::PageUp,
+            b"\x1b[6~" => Key::PageDown,
+            b"\x1b[H" | b"\x1b[1~" | b"\x1bOH" => Key::Home,
+            b"\x1b[F" | b"\x1b[4~" | b"\x1bOF" => Key::End,
+            b"\n" | b"\r" => Key::Enter,
+            b"\x7f" | b"\x08" => Key::Backspace,
+            [b'\x1b', ..] => Key::Escape,
+            // ctrl-c, since it doesn't interrupt any more
+            b"\x03" => Key::Char('q'),
+            bytes => Key::Char(String::from_utf8_lossy(bytes).chars().next()?),
+        };
+        Some(key)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        self.write("\x1b[?25h\x1b[?1049l");
+        let _ = stty(&self.tty, &[self.saved.trim()]);
+    }
+}
+
+fn stty(tty: &File, args: &[&str]) -> Result<String, String> {
+    let stdin = tty
+        .try_clone()
+        .map_err(|err| format!("couldn't set up the terminal: {}", err))?;
+    match Command::new("stty").args(args).stdin(stdin).output() {
+        Ok(output) if output.status.success() => {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        Ok(output) => Err(format!(
+            "stty failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(err) => Err(format!("couldn't run stty: {}", err)),
+    }
+}
+
+struct Stepper {
+    title: String,
+    last: usize,
+    step: usize,
+    playing: bool,
+    // steps per tenth of a second while playing
+    speed: usize,
+    // the top left of the part of the picture on screen, once there's been a picture
+    pan: Option<Vec2>,
+    // the step number being typed in, when jumping
+    prompt: Option<String>,
+}
+
+impl Stepper {
+    fn run<S: Solution>(&mut self, terminal: &mut Terminal, frames: &mut FrameCache<S>) {
+        let mut drawn = None;
+        loop {
+            // only draw when something's changed, the size of the terminal included
+            let size = terminal.size();
+            let state = (
+                self.step,
+                size,
+                self.pan,
+                self.playing,
+                self.speed,
+                self.prompt.clone(),
+            );
+            if drawn != Some(state.clone()) {
+                let screen = self.render(frames.get(self.step), size);
+                terminal.write(&screen);
+                drawn = Some(state);
+            }
+
+            match terminal.key() {
+                Some(key) if !self.press(key) => return,
+                Some(_key) => {}
+                None if self.playing => {
+                    self.step = (self.step + self.speed).min(self.last);
+                    self.playing = self.step < self.last;
+                }
+                None => {}
+            }
+        }
+    }
+
+    // act on a key, returning false to quit
+    fn press(&mut self, key: Key) -> bool {
+        if let Some(prompt) = &mut self.prompt {
+            match key {
+                Key::Char(c) if c.is_ascii_digit() => prompt.push(c),
+                Key::Backspace => {
+                    prompt.pop();
+                }
+                Key::Enter => {
+                    if let Ok(step) = prompt.parse::<usize>() {
+                        self.step = step.min(self.last);
+                    }
+                    self.prompt = None;
+                }
+                Key::Escape => self.prompt = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        let pan = self.pan.unwrap_or(Vec2::ZERO);
+        match key {
+            Key::Char('q') | Key::Escape => return false,
+            Key::Left | Key::Char('h') => self.back(1),
+            Key::Right | Key::Char('l') => self.forward(1),
+            Key::PageUp | Key::Up => self.back(10),
+            Key::PageDown | Key::Down => self.forward(10),
+            Key::Home => self.back(self.last),
+            Key::End => self.forward(self.last),
+            Key::Char(' ') => self.playing = !self.playing && self.step < self.last,
+            Key::Char('+') | Key::Char('=') => self.speed = (self.speed * 2).min(1 << 16),
+            Key::Char('-') => self.speed = (self.speed / 2).max(1),
+            Key::Char('g') => {
+                self.playing = false;
+                self.prompt = Some(String::new());
+            }
+            Key::Char('w') => self.pan = Some(pan + Vec2::UP * 4),
+            Key::Char('a') => self.pan = Some(pan + Vec2::LEFT * 8),
+            Key::Char('s') => self.pan = Some(pan + Vec2::DOWN * 4),
+            Key::Char('d') => self.pan = Some(pan + Vec2::RIGHT * 8),
+            _ => {}
+        }
+        true
+    }
+
+    fn back(&mut self, steps: usize) {
+        self.playing = false;
+        self.step = self.step.saturating_sub(steps);
+    }
+
+    fn forward(&mut self, steps: usize) {
+        self.playing = false;
+        self.step = (self.step + steps).min(self.last);
+    }
+
+    // the whole screen for `frame`, drawn over the last one rather than clearing it first,
+    // which would flicker
+    fn render(&mut self, frame: &Frame, (columns, rows): (usize, usize)) -> String {
+        let picture = &frame.picture;
+        let (width, height) = (picture.width() as i32, picture.height() as i32);
+        // the title, counters and help take three lines
+        let view = Vec2::new(columns as i32, rows.saturating_sub(3) as i32);
+
+        // start off looking at the middle of the top, and never pan off the picture
+        let pan = self.pan.unwrap_or(Vec2::new((width - view.x) / 2, 0));
+        let pan = Vec2::new(
+            pan.x.min(width - view.x).max(0),
+            pan.y.min(height - view.y).max(0),
+        );
+        self.pan = Some(pan);
+
+        let mut screen = String::from("\x1b[H");
+        let mut status = format!("{}  step {}/{}", self.title, self.step, self.last);
+        for (name, count) in &frame.counters {
+            write!(status, "  {} {}", name, count).unwrap();
+        }
+        if self.playing {
+            write!(status, "  playing x{}", self.speed).unwrap();
+        }
+        line(&mut screen, &status, columns);
+
+        for y in pan.y..(pan.y + view.y).min(height) {
+            let row =
+                &picture.row(y as usize)[pan.x as usize..(pan.x + view.x).min(width) as usize];
+            line(&mut screen, &row.iter().collect::<String>(), columns);
+        }
+        screen.push_str("\x1b[J");
+
+        // the bottom line has the help, or the step being jumped to
+        write!(screen, "\x1b[{};1H", rows.max(1)).unwrap();
+        let bottom = match &self.prompt {
+            Some(prompt) => format!("jump to step: {}_", prompt),
+            None => String::from(HELP),
+        };
+        screen.extend(bottom.chars().take(columns));
+        screen.push_str("\x1b[K");
+        screen
+    }
+}
+
+// a line of the screen, cut off at its edge, clearing whatever was there before
+fn line(screen: &mut String, text: &str, columns: usize) {
+    screen.extend(text.chars().take(columns));
+    screen.push_str("\x1b[K\r\n");
+}
@@ -0,0 +1,78 @@
+use std::{cmp::Reverse, time::Duration};
+
+use crate::solution::Solved;
+
+#[derive(Debug, Default)]
+struct DayTiming {
+    day: String,
+    parse: Duration,
+    part1: Duration,
+    part2: Duration,
+}
+
+impl DayTiming {
+    fn total(&self) -> Duration {
+        self.parse + self.part1 + self.part2
+    }
+}
+
+/// Per-day totals across every input that day ran against.
+#[derive(Debug, Default)]
+pub struct Timings {
+    days: Vec<DayTiming>,
+}
+
+impl Timings {
+    pub fn add(&mut self, solved: &Solved) {
+        if self.days.last().is_none_or(|last| last.day != solved.day) {
+            self.days.push(DayTiming {
+                day: solved.day.clone(),
+                ..Default::default()
+            });
+        }
+        let timing = self.days.last_mut().unwrap();
+
+        timing.parse += solved.parse_time;
+        for answer in &solved.answers {
+            match answer.part {
+                1 => timing.part1 += answer.elapsed,
+                _ => timing.part2 += answer.elapsed,
+            }
+        }
+    }
+
+    // slowest day first, so the ones worth looking at are at the top
+    pub fn print(&self) {
+        let mut days: Vec<&DayTiming> = self.days.iter().collect();
+        days.sort_by_key(|timing| Reverse(timing.total()));
+
+        let mut total = DayTiming {
+            day: String::from("Total"),
+            ..Default::default()
+        };
+
+        println!();
+        println!(
+            "{:<8}{:>12}{:>12}{:>12}{:>12}",
+            "Day", "Parse", "Part 1", "Part 2", "Total"
+        );
+        for timing in days {
+            print_row(timing);
+            total.parse += timing.parse;
+            total.part1 += timing.part1;
+            total.part2 += timing.part2;
+        }
+        print_row(&total);
+    }
+}
+
+fn print_row(timing: &DayTiming) {
+    println!(
+        "{:<8}{:>12}{:>12}{:>12}{:>12}",
+        timing.day,
+        format!("{:.2?}", timing.parse),
+        format!("{:.2?}", timing.part1),
+        format!("{:.2?}", timing.part2),
+        format!("{:.2?}", timing.total()),
+    );
+}